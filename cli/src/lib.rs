@@ -6,7 +6,10 @@ use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 use vcm_common::client;
 use vcm_common::dirs::socket_path;
 use vcm_daemon::config::{Config, SpeechModel};
-use vcm_proto::{Empty, SetLanguageRequest, State, status::Status as StatusVariant};
+use vcm_proto::{
+    AddVocabularyTermsRequest, Empty, SetLanguageRequest, SetModelRequest, SetTaskRequest, State,
+    Task, status::Status as StatusVariant,
+};
 
 #[derive(Parser)]
 #[command(name = "vcmctl")]
@@ -27,6 +30,8 @@ enum Commands {
     Status,
     /// Toggle listening on/off
     Toggle,
+    /// Delete the last dictated utterance
+    Undo,
     /// Manage configuration
     Config {
         #[command(subcommand)]
@@ -37,6 +42,23 @@ enum Commands {
         #[command(subcommand)]
         action: LanguageAction,
     },
+    /// Transcribe speech as spoken or translate it to English
+    Task {
+        #[command(subcommand)]
+        action: TaskAction,
+    },
+    /// Switch the speech model
+    Model {
+        #[command(subcommand)]
+        action: ModelAction,
+    },
+    /// List audio input devices
+    Devices,
+    /// Manage custom vocabulary
+    Vocabulary {
+        #[command(subcommand)]
+        action: VocabularyAction,
+    },
 }
 
 #[derive(Subcommand)]
@@ -54,6 +76,8 @@ enum ConfigAction {
     },
     /// Show current configuration
     Show,
+    /// Apply changes in the configuration file to the running daemon
+    Reload,
 }
 
 #[derive(Subcommand)]
@@ -67,6 +91,56 @@ enum LanguageAction {
     },
 }
 
+#[derive(Subcommand)]
+enum TaskAction {
+    /// Show current task
+    Get,
+    /// Switch task
+    Set {
+        #[arg(value_enum)]
+        task: TaskArg,
+    },
+}
+
+#[derive(Clone, ValueEnum)]
+enum TaskArg {
+    /// Type text in the spoken language
+    Transcribe,
+    /// Type the English translation
+    Translate,
+}
+
+impl From<TaskArg> for Task {
+    fn from(arg: TaskArg) -> Self {
+        match arg {
+            TaskArg::Transcribe => Task::Transcribe,
+            TaskArg::Translate => Task::Translate,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum ModelAction {
+    /// Switch to a model without restarting, downloading it if needed
+    Use {
+        #[arg(value_enum)]
+        model: ModelArg,
+    },
+}
+
+#[derive(Subcommand)]
+enum VocabularyAction {
+    /// Add terms to spell as written (e.g. product names)
+    Add {
+        /// Terms to add
+        #[arg(required = true)]
+        terms: Vec<String>,
+        /// Only use the terms for this language (code or name)
+        #[arg(long)]
+        language: Option<String>,
+    },
+}
+
 #[derive(Clone, ValueEnum)]
 enum ModelArg {
     WhisperTiny,
@@ -249,7 +323,10 @@ async fn handle_init_event(
         Some(EventType::DaemonError(err)) => {
             handle_daemon_error(err, grpc_client).await?;
         }
-        Some(EventType::StateChange(_) | EventType::Transcription(_)) | None => {}
+        Some(
+            EventType::StateChange(_) | EventType::Transcription(_) | EventType::ModelSwitch(_),
+        )
+        | None => {}
     }
     Ok(false)
 }
@@ -332,9 +409,30 @@ async fn cmd_status() -> Result<()> {
             match state {
                 State::Stopped => println!("Stopped"),
                 State::Listening => println!("Listening"),
+                State::Paused if h.models_unloaded => {
+                    println!("Paused (models unloaded, they load again when listening starts)")
+                }
                 State::Paused => println!("Paused"),
                 State::Initializing => println!("Initializing..."),
             }
+            if !h.detected_language.is_empty() {
+                println!(
+                    "Last detected language: {} ({:.0}%)",
+                    h.detected_language,
+                    h.detected_language_probability * 100.0
+                );
+            }
+            if !h.model.is_empty() {
+                println!("Model: {}", h.model);
+            }
+            if let Some(counts) = h.filter_counts
+                && counts.blocklisted + counts.repetitions + counts.too_fast > 0
+            {
+                println!(
+                    "Filtered hallucinations: {} blocklisted, {} repetitions, {} too fast",
+                    counts.blocklisted, counts.repetitions, counts.too_fast
+                );
+            }
         }
         Some(StatusVariant::Error(e)) => {
             println!("Error: {}", e.message);
@@ -450,6 +548,248 @@ async fn cmd_language_set(code: String) -> Result<()> {
     Ok(())
 }
 
+fn cmd_config_show() -> Result<()> {
+    let path = Config::config_path()?;
+    if !path.exists() {
+        println!("No config file found at: {}", path.display());
+        println!("Using defaults. Run 'vcmctl config init' to create one.");
+        println!();
+    }
+
+    let mut config = Config::load()?;
+    if config.http.api_key.is_some() {
+        config.http.api_key = Some("(set)".to_string());
+    }
+    println!("Config path: {}", path.display());
+    println!();
+    print!("{}", config.to_toml()?);
+
+    Ok(())
+}
+
+async fn cmd_undo() -> Result<()> {
+    let sock_path = socket_path()?;
+
+    if !client::is_daemon_running(&sock_path).await {
+        println!("Daemon not running");
+        return Ok(());
+    }
+
+    let mut client = client::connect(&sock_path).await?;
+    let response = client.undo_last(Empty {}).await.context("Failed to undo")?;
+
+    println!("Deleted {} characters", response.into_inner().deleted_chars);
+
+    Ok(())
+}
+
+async fn cmd_config_reload() -> Result<()> {
+    let sock_path = socket_path()?;
+
+    if !client::is_daemon_running(&sock_path).await {
+        println!("Daemon not running");
+        return Ok(());
+    }
+
+    let mut client = client::connect(&sock_path).await?;
+    client
+        .reload_config(Empty {})
+        .await
+        .context("Failed to reload configuration")?;
+
+    println!("Configuration reloaded");
+
+    Ok(())
+}
+
+async fn cmd_task_get() -> Result<()> {
+    let sock_path = socket_path()?;
+
+    if !client::is_daemon_running(&sock_path).await {
+        println!("Daemon not running");
+        return Ok(());
+    }
+
+    let mut client = client::connect(&sock_path).await?;
+    let response = client
+        .get_language(Empty {})
+        .await
+        .context("Failed to get task")?;
+
+    println!("Task: {}", task_name(response.into_inner().task()));
+
+    Ok(())
+}
+
+async fn cmd_task_set(task: TaskArg) -> Result<()> {
+    let sock_path = socket_path()?;
+
+    if !client::is_daemon_running(&sock_path).await {
+        println!("Daemon not running");
+        return Ok(());
+    }
+
+    let mut client = client::connect(&sock_path).await?;
+    client
+        .set_task(SetTaskRequest {
+            task: Task::from(task).into(),
+        })
+        .await
+        .context("Failed to set task")?;
+
+    let response = client
+        .get_language(Empty {})
+        .await
+        .context("Failed to get task")?
+        .into_inner();
+
+    println!("Task set to: {}", task_name(response.task()));
+
+    Ok(())
+}
+
+fn task_name(task: Task) -> &'static str {
+    match task {
+        Task::Transcribe => "transcribe",
+        Task::Translate => "translate",
+    }
+}
+
+async fn cmd_model_use(model: ModelArg) -> Result<()> {
+    use vcm_proto::event::Event as EventType;
+    use vcm_proto::model_switch::Progress;
+
+    let sock_path = socket_path()?;
+
+    if !client::is_daemon_running(&sock_path).await {
+        println!("Daemon not running");
+        return Ok(());
+    }
+
+    let model_name = model
+        .to_possible_value()
+        .context("Model has no name")?
+        .get_name()
+        .to_string();
+
+    let mut client = client::connect(&sock_path).await?;
+    // Subscribe first so no progress is missed
+    let mut stream = client::subscribe(&mut client).await?;
+    client
+        .set_model(SetModelRequest {
+            model: model_name.clone(),
+        })
+        .await
+        .context("Failed to switch model")?;
+
+    let mut downloading = false;
+    while let Some(event) = stream.message().await? {
+        let Some(EventType::ModelSwitch(switch)) = event.event else {
+            continue;
+        };
+        if switch.model_name != model_name {
+            continue;
+        }
+        match switch.progress {
+            Some(Progress::ModelDownload(dl)) => {
+                let mb_done = dl.bytes_downloaded as f64 / 1_000_000.0;
+                let mb_total = dl.bytes_total as f64 / 1_000_000.0;
+                if mb_total > 0.0 {
+                    print!(
+                        "\rDownloading {}... {:.0}/{:.0} MB",
+                        dl.model_name, mb_done, mb_total
+                    );
+                } else {
+                    print!("\rDownloading {}... {:.0} MB", dl.model_name, mb_done);
+                }
+                use std::io::Write;
+                std::io::stdout().flush().ok();
+                downloading = true;
+            }
+            Some(Progress::ModelLoad(load)) => {
+                if downloading {
+                    println!();
+                }
+                println!("Loading {}...", load.model_name);
+            }
+            Some(Progress::Ready(_)) => {
+                println!("Model switched to: {model_name}");
+                return Ok(());
+            }
+            Some(Progress::Error(e)) => {
+                anyhow::bail!("Failed to switch model: {}", e.message);
+            }
+            None => {}
+        }
+    }
+
+    anyhow::bail!("Daemon disconnected before the model switch finished")
+}
+
+async fn cmd_vocabulary_add(terms: Vec<String>, language: Option<String>) -> Result<()> {
+    let sock_path = socket_path()?;
+
+    if !client::is_daemon_running(&sock_path).await {
+        println!("Daemon not running");
+        return Ok(());
+    }
+
+    let mut client = client::connect(&sock_path).await?;
+    let response = client
+        .add_vocabulary_terms(AddVocabularyTermsRequest {
+            terms,
+            language: language.unwrap_or_default(),
+        })
+        .await
+        .context("Failed to add vocabulary terms")?;
+
+    match response.into_inner().added {
+        0 => println!("No new terms"),
+        1 => println!("Added 1 term"),
+        n => println!("Added {n} terms"),
+    }
+
+    Ok(())
+}
+
+async fn cmd_devices() -> Result<()> {
+    let sock_path = socket_path()?;
+
+    if !client::is_daemon_running(&sock_path).await {
+        println!("Daemon not running");
+        return Ok(());
+    }
+
+    let mut client = client::connect(&sock_path).await?;
+    let response = client
+        .list_audio_devices(Empty {})
+        .await
+        .context("Failed to list audio devices")?
+        .into_inner();
+
+    if response.devices.is_empty() {
+        println!("No input devices found");
+        return Ok(());
+    }
+
+    for device in &response.devices {
+        let marker = if device.is_selected { "*" } else { " " };
+        let default = if device.is_default { " (default)" } else { "" };
+        println!("{} {}{}", marker, device.name, default);
+        println!("    id: {}", device.id);
+    }
+
+    if response.configured_device_missing {
+        println!();
+        println!(
+            "Configured device '{}' is not connected",
+            response.configured_device
+        );
+    }
+
+    Ok(())
+}
+
 pub async fn run() -> Result<()> {
     tracing_subscriber::registry()
         .with(fmt::layer())
@@ -463,10 +803,24 @@ pub async fn run() -> Result<()> {
         Commands::Stop => cmd_stop().await?,
         Commands::Status => cmd_status().await?,
         Commands::Toggle => cmd_toggle().await?,
+        Commands::Undo => cmd_undo().await?,
+        Commands::Devices => cmd_devices().await?,
         Commands::Language { action } => match action {
             LanguageAction::Get => cmd_language_get().await?,
             LanguageAction::Set { code } => cmd_language_set(code).await?,
         },
+        Commands::Task { action } => match action {
+            TaskAction::Get => cmd_task_get().await?,
+            TaskAction::Set { task } => cmd_task_set(task).await?,
+        },
+        Commands::Model { action } => match action {
+            ModelAction::Use { model } => cmd_model_use(model).await?,
+        },
+        Commands::Vocabulary { action } => match action {
+            VocabularyAction::Add { terms, language } => {
+                cmd_vocabulary_add(terms, language).await?
+            }
+        },
         Commands::Config { action } => match action {
             ConfigAction::Path => {
                 let path = Config::config_path()?;
@@ -489,25 +843,8 @@ pub async fn run() -> Result<()> {
                 println!("Model: {:?}", config.model.model);
                 println!("Language: {:?}", config.model.language);
             }
-            ConfigAction::Show => {
-                let path = Config::config_path()?;
-                if !path.exists() {
-                    println!("No config file found at: {}", path.display());
-                    println!("Using defaults. Run 'vcmctl config init' to create one.");
-                    println!();
-                }
-
-                let config = Config::load()?;
-                println!("Config path: {}", path.display());
-                println!();
-                println!("[model]");
-                println!("model = {:?}", config.model.model);
-                println!("language = {:?}", config.model.language);
-                println!();
-                println!("[latency]");
-                println!("mode = {:?}", config.latency.mode);
-                println!("min_chunk_seconds = {}", config.latency.min_chunk_seconds);
-            }
+            ConfigAction::Show => cmd_config_show()?,
+            ConfigAction::Reload => cmd_config_reload().await?,
         },
     }
 
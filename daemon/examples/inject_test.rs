@@ -20,8 +20,8 @@ use tokio_util::sync::CancellationToken;
 use tracing::error;
 use tracing_subscriber::EnvFilter;
 use vcm_daemon::config::Config;
-use vcm_daemon::engine::Engine;
-use vcm_daemon::inject::KeystrokeInjector;
+use vcm_daemon::engine::{Engine, EngineEvent};
+use vcm_daemon::inject::{KeystrokeInjector, TextInjector};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -85,12 +85,17 @@ async fn main() -> anyhow::Result<()> {
             r.store(false, Ordering::SeqCst);
         }
 
-        result = engine.run_loop(cancel, |text| {
+        result = engine.run_loop(cancel, |event| {
+            let EngineEvent::Transcription(transcript) = event else {
+                return;
+            };
+            let text = transcript.text;
+
             // Print the transcription for visibility
             println!(">>> {}", text);
 
             // Inject the text as keystrokes
-            if let Err(e) = injector.inject_text(text) {
+            if let Err(e) = injector.inject_text(&text) {
                 error!(error = %e, "Keystroke injection failed");
             }
         }) => {
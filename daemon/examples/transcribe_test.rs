@@ -15,7 +15,7 @@ use tokio::signal;
 use tokio_util::sync::CancellationToken;
 use tracing_subscriber::EnvFilter;
 use vcm_daemon::config::Config;
-use vcm_daemon::engine::Engine;
+use vcm_daemon::engine::{Engine, EngineEvent};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -67,8 +67,17 @@ async fn main() -> anyhow::Result<()> {
             r.store(false, Ordering::SeqCst);
         }
 
-        result = engine.run_loop(cancel, |text| {
-            println!(">>> {}", text);
+        result = engine.run_loop(cancel, |event| match event {
+            EngineEvent::Transcription(transcript) => {
+                println!(">>> {} ({:.2})", transcript.text, transcript.confidence())
+            }
+            EngineEvent::Partial(transcript) => println!("... {}", transcript.text),
+            EngineEvent::Filtered(reason) => eprintln!("Filtered: {:?}", reason),
+            EngineEvent::AudioError(message) => eprintln!("Audio error: {}", message),
+            EngineEvent::AudioRecovered => eprintln!("Audio recovered"),
+            EngineEvent::SegmentsDropped(dropped) => {
+                eprintln!("Dropped {} segments, fell behind", dropped.count)
+            }
         }) => {
             if let Err(e) = result {
                 eprintln!("Engine error: {:#}", e);
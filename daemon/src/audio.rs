@@ -1,12 +1,19 @@
 //! Audio capture and processing for VCM daemon.
 //!
-//! Handles microphone input capture and resampling to 16kHz mono for speech recognition.
+//! Handles audio input (microphone, files, stdin) and resampling to 16kHz mono
+//! for speech recognition.
 
+use crate::config::{AudioConfig, AudioSourceKind, DeviceFallback, PcmFormat};
 use anyhow::{Context, Result};
 use audioadapter_buffers::direct::SequentialSliceOfVecs;
 use rubato::audioadapter::Adapter;
 use rubato::{Fft, FixedSync, Resampler};
+use std::collections::VecDeque;
+use std::io::Read;
+use std::path::Path;
 use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 
 /// Target sample rate for speech recognition models.
 pub const TARGET_SAMPLE_RATE: u32 = 16000;
@@ -151,10 +158,182 @@ impl AudioResampler {
     }
 }
 
-/// Audio capture from the default input device.
+/// A source of mono audio samples feeding the engine.
+///
+/// Implementations deliver samples at their own native rate; the engine
+/// resamples to [`TARGET_SAMPLE_RATE`] before VAD.
+pub trait AudioSource: Send {
+    /// Sample rate of the samples returned by `try_recv`, in Hz.
+    fn sample_rate(&self) -> u32;
+
+    /// Try to receive available audio samples (non-blocking).
+    /// Returns mono samples at the source's sample rate.
+    fn try_recv(&mut self) -> Option<Vec<f32>>;
+
+    /// Whether the source is exhausted and will never yield more samples.
+    ///
+    /// Live sources never finish; files, stdin and scripted sources finish
+    /// once all their samples have been returned.
+    fn is_finished(&self) -> bool {
+        false
+    }
+
+    /// Take the error that killed the source, if any.
+    ///
+    /// Once this returns `Some` the source will not deliver more samples and
+    /// should be stopped and reopened.
+    fn poll_error(&mut self) -> Option<String> {
+        None
+    }
+
+    /// Stop the source and release its resources.
+    fn stop(self: Box<Self>) {}
+}
+
+/// Open the audio source selected by the configuration.
+pub fn open_source(config: &AudioConfig) -> Result<Box<dyn AudioSource>> {
+    match config.source {
+        AudioSourceKind::Microphone => Ok(Box::new(AudioCapture::start_with_device(
+            config.device.as_deref(),
+            config.device_fallback,
+        )?)),
+        AudioSourceKind::File => {
+            let path = config
+                .path
+                .as_ref()
+                .context("audio.path must be set when audio.source = \"file\"")?;
+            Ok(Box::new(FileSource::open(path)?))
+        }
+        AudioSourceKind::Stdin => Ok(Box::new(StdinSource::start(
+            config.stdin_format,
+            config.stdin_sample_rate,
+            config.stdin_channels,
+        )?)),
+    }
+}
+
+/// An audio input device as reported by the host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputDeviceInfo {
+    /// Stable identifier (`<host>:<device>`), survives reconnects where the host supports it.
+    pub id: String,
+    /// Human-readable device name.
+    pub name: String,
+    /// Whether this is the system default input device.
+    pub is_default: bool,
+}
+
+/// List the input devices available on the default host.
+pub fn list_input_devices() -> Result<Vec<InputDeviceInfo>> {
+    use cpal::traits::HostTrait;
+
+    let host = cpal::default_host();
+    let default_id = host.default_input_device().and_then(|d| device_id(&d));
+
+    let devices = host
+        .input_devices()
+        .context("Failed to enumerate input devices")?
+        .map(|device| describe_device(&device, default_id.as_deref()))
+        .collect();
+
+    Ok(devices)
+}
+
+/// Find the index of the device matching `selector`.
+///
+/// An exact id match wins, then a case-insensitive exact name match, then the
+/// first device whose name contains the selector (case-insensitive).
+pub fn match_device(devices: &[InputDeviceInfo], selector: &str) -> Option<usize> {
+    let selector_lower = selector.to_lowercase();
+    devices
+        .iter()
+        .position(|d| d.id == selector)
+        .or_else(|| {
+            devices
+                .iter()
+                .position(|d| d.name.to_lowercase() == selector_lower)
+        })
+        .or_else(|| {
+            devices
+                .iter()
+                .position(|d| d.name.to_lowercase().contains(&selector_lower))
+        })
+}
+
+fn device_id(device: &cpal::Device) -> Option<String> {
+    use cpal::traits::DeviceTrait;
+    device.id().ok().map(|id| id.to_string())
+}
+
+fn describe_device(device: &cpal::Device, default_id: Option<&str>) -> InputDeviceInfo {
+    use cpal::traits::DeviceTrait;
+
+    let id = device_id(device).unwrap_or_default();
+    let name = device
+        .description()
+        .map(|d| d.name().to_string())
+        .unwrap_or_else(|_| "<unknown>".to_string());
+    let is_default = !id.is_empty() && default_id == Some(id.as_str());
+    InputDeviceInfo {
+        id,
+        name,
+        is_default,
+    }
+}
+
+/// Resolve the configured device, applying the fallback policy when it is missing.
+fn select_device(
+    host: &cpal::Host,
+    selector: Option<&str>,
+    fallback: DeviceFallback,
+) -> Result<cpal::Device> {
+    use cpal::traits::HostTrait;
+
+    if let Some(selector) = selector {
+        let default_id = host.default_input_device().and_then(|d| device_id(&d));
+        let devices: Vec<cpal::Device> = host
+            .input_devices()
+            .context("Failed to enumerate input devices")?
+            .collect();
+        let infos: Vec<InputDeviceInfo> = devices
+            .iter()
+            .map(|d| describe_device(d, default_id.as_deref()))
+            .collect();
+
+        if let Some(index) = match_device(&infos, selector) {
+            let found = &infos[index];
+            info!(id = %found.id, name = %found.name, "Using configured input device");
+            return Ok(devices.into_iter().nth(index).expect("index is in range"));
+        }
+
+        match fallback {
+            DeviceFallback::Default => {
+                warn!(
+                    device = selector,
+                    "Configured input device not found; falling back to system default"
+                );
+            }
+            DeviceFallback::Fail => {
+                anyhow::bail!("Configured input device '{}' not found", selector);
+            }
+        }
+    }
+
+    host.default_input_device()
+        .context("No input device available")
+}
+
+/// How long a live stream may go without delivering samples before it is
+/// considered dead. Some backends stop calling back on unplug without ever
+/// reporting an error.
+const STREAM_STALL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Audio capture from an input device.
 pub struct AudioCapture {
     stream: cpal::Stream,
     receiver: mpsc::Receiver<Vec<f32>>,
+    errors: mpsc::Receiver<String>,
+    last_data: Instant,
     sample_rate: u32,
     channels: u16,
 }
@@ -162,12 +341,18 @@ pub struct AudioCapture {
 impl AudioCapture {
     /// Start capturing audio from the default input device.
     pub fn start() -> Result<Self> {
-        use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+        Self::start_with_device(None, DeviceFallback::Default)
+    }
+
+    /// Start capturing from the device matching `selector` (id or name).
+    ///
+    /// `None` uses the system default. When the selector matches nothing,
+    /// `fallback` decides between the default device and an error.
+    pub fn start_with_device(selector: Option<&str>, fallback: DeviceFallback) -> Result<Self> {
+        use cpal::traits::{DeviceTrait, StreamTrait};
 
         let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .context("No input device available")?;
+        let device = select_device(&host, selector, fallback)?;
 
         let config = device
             .default_input_config()
@@ -177,8 +362,11 @@ impl AudioCapture {
         let channels = config.channels();
 
         let (sender, receiver) = mpsc::channel();
+        let (err_sender, errors) = mpsc::channel();
 
-        let err_fn = |err| eprintln!("Audio stream error: {}", err);
+        let err_fn = move |err: cpal::StreamError| {
+            let _ = err_sender.send(err.to_string());
+        };
 
         let stream = match config.sample_format() {
             cpal::SampleFormat::F32 => device.build_input_stream(
@@ -219,6 +407,8 @@ impl AudioCapture {
         Ok(Self {
             stream,
             receiver,
+            errors,
+            last_data: Instant::now(),
             sample_rate,
             channels,
         })
@@ -260,6 +450,284 @@ impl AudioCapture {
     }
 }
 
+impl AudioSource for AudioCapture {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn try_recv(&mut self) -> Option<Vec<f32>> {
+        let samples = AudioCapture::try_recv(self)?;
+        self.last_data = Instant::now();
+        Some(samples)
+    }
+
+    fn poll_error(&mut self) -> Option<String> {
+        if let Ok(err) = self.errors.try_recv() {
+            return Some(format!("audio stream error: {err}"));
+        }
+        if self.last_data.elapsed() > STREAM_STALL_TIMEOUT {
+            return Some(format!(
+                "no audio received for {}s",
+                STREAM_STALL_TIMEOUT.as_secs()
+            ));
+        }
+        None
+    }
+
+    fn stop(self: Box<Self>) {
+        AudioCapture::stop(*self);
+    }
+}
+
+/// Number of blocks per second a file or scripted source is split into.
+const BLOCKS_PER_SECOND: u32 = 10;
+
+/// In-memory source that replays a fixed list of sample blocks.
+///
+/// Each `try_recv` call returns the next block; useful for tests and for
+/// driving the pipeline with synthetic audio.
+pub struct ScriptedSource {
+    blocks: VecDeque<Vec<f32>>,
+    sample_rate: u32,
+}
+
+impl ScriptedSource {
+    /// Create a source that yields `blocks` in order.
+    pub fn new(blocks: Vec<Vec<f32>>, sample_rate: u32) -> Self {
+        Self {
+            blocks: blocks.into(),
+            sample_rate,
+        }
+    }
+
+    /// Create a source that yields `samples` split into 100ms blocks.
+    pub fn from_samples(samples: &[f32], sample_rate: u32) -> Self {
+        let block_len = (sample_rate / BLOCKS_PER_SECOND).max(1) as usize;
+        let blocks = samples.chunks(block_len).map(<[f32]>::to_vec).collect();
+        Self::new(blocks, sample_rate)
+    }
+}
+
+impl AudioSource for ScriptedSource {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn try_recv(&mut self) -> Option<Vec<f32>> {
+        self.blocks.pop_front()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+/// Source that replays a WAV or FLAC file.
+///
+/// The file is decoded up front and delivered in 100ms blocks, as fast as
+/// the engine polls — replay is not paced to real time.
+pub struct FileSource {
+    inner: ScriptedSource,
+}
+
+impl FileSource {
+    /// Decode the file at `path`. The format is chosen by extension
+    /// (`.flac`, otherwise WAV).
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let is_flac = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("flac"));
+
+        let buffer = if is_flac {
+            decode_flac(path)
+        } else {
+            decode_wav(path)
+        }
+        .with_context(|| format!("Failed to decode audio file {}", path.display()))?;
+
+        info!(
+            path = %path.display(),
+            sample_rate = buffer.sample_rate,
+            duration_secs = buffer.duration_secs(),
+            "Opened audio file source"
+        );
+
+        Ok(Self {
+            inner: ScriptedSource::from_samples(&buffer.samples, buffer.sample_rate),
+        })
+    }
+}
+
+impl AudioSource for FileSource {
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn try_recv(&mut self) -> Option<Vec<f32>> {
+        self.inner.try_recv()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+}
+
+/// Decode a WAV file into a mono buffer.
+fn decode_wav(path: &Path) -> Result<AudioBuffer> {
+    let mut reader = hound::WavReader::open(path).context("Failed to open WAV file")?;
+    let spec = reader.spec();
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<std::result::Result<_, _>>()
+            .context("Failed to read WAV samples")?,
+        hound::SampleFormat::Int => {
+            let max_val = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / max_val))
+                .collect::<std::result::Result<_, _>>()
+                .context("Failed to read WAV samples")?
+        }
+    };
+
+    Ok(AudioBuffer::new(
+        to_mono(&samples, spec.channels),
+        spec.sample_rate,
+    ))
+}
+
+/// Decode a FLAC file into a mono buffer.
+fn decode_flac(path: &Path) -> Result<AudioBuffer> {
+    let mut reader = claxon::FlacReader::open(path).context("Failed to open FLAC file")?;
+    let info = reader.streaminfo();
+    let max_val = (1i64 << (info.bits_per_sample - 1)) as f32;
+
+    let samples: Vec<f32> = reader
+        .samples()
+        .map(|s| s.map(|s| s as f32 / max_val))
+        .collect::<std::result::Result<_, _>>()
+        .context("Failed to read FLAC samples")?;
+
+    Ok(AudioBuffer::new(
+        to_mono(&samples, info.channels as u16),
+        info.sample_rate,
+    ))
+}
+
+/// Source that reads raw interleaved PCM from standard input.
+///
+/// A background thread reads stdin and forwards decoded mono samples; the
+/// source finishes when stdin reaches EOF.
+pub struct StdinSource {
+    receiver: mpsc::Receiver<Vec<f32>>,
+    sample_rate: u32,
+    finished: bool,
+}
+
+/// Bytes read from stdin per iteration of the reader thread.
+const STDIN_READ_SIZE: usize = 8192;
+
+impl StdinSource {
+    /// Start reading PCM from stdin in the given format.
+    pub fn start(format: PcmFormat, sample_rate: u32, channels: u16) -> Result<Self> {
+        if sample_rate == 0 || channels == 0 {
+            anyhow::bail!("stdin sample rate and channel count must be non-zero");
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let frame_bytes = format.bytes_per_sample() * channels as usize;
+
+        std::thread::Builder::new()
+            .name("vcm-stdin-audio".to_string())
+            .spawn(move || {
+                let mut stdin = std::io::stdin().lock();
+                let mut pending = Vec::new();
+                let mut buf = [0u8; STDIN_READ_SIZE];
+                loop {
+                    let n = match stdin.read(&mut buf) {
+                        Ok(0) => break,
+                        Ok(n) => n,
+                        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                        Err(e) => {
+                            tracing::error!(error = %e, "Failed to read audio from stdin");
+                            break;
+                        }
+                    };
+                    pending.extend_from_slice(&buf[..n]);
+                    let whole = pending.len() - pending.len() % frame_bytes;
+                    if whole == 0 {
+                        continue;
+                    }
+                    let samples = decode_pcm(&pending[..whole], format);
+                    pending.drain(..whole);
+                    if sender.send(to_mono(&samples, channels)).is_err() {
+                        break;
+                    }
+                }
+                debug!("Stdin audio reader finished");
+            })
+            .context("Failed to spawn stdin reader thread")?;
+
+        Ok(Self {
+            receiver,
+            sample_rate,
+            finished: false,
+        })
+    }
+}
+
+impl AudioSource for StdinSource {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn try_recv(&mut self) -> Option<Vec<f32>> {
+        let mut all_samples = Vec::new();
+        loop {
+            match self.receiver.try_recv() {
+                Ok(samples) => all_samples.extend(samples),
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.finished = true;
+                    break;
+                }
+            }
+        }
+        (!all_samples.is_empty()).then_some(all_samples)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+impl PcmFormat {
+    /// Size of one sample in bytes.
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            PcmFormat::S16le => 2,
+            PcmFormat::F32le => 4,
+        }
+    }
+}
+
+/// Decode little-endian PCM bytes into f32 samples. Trailing partial samples are ignored.
+pub fn decode_pcm(bytes: &[u8], format: PcmFormat) -> Vec<f32> {
+    match format {
+        PcmFormat::S16le => bytes
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+            .collect(),
+        PcmFormat::F32le => bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+    }
+}
+
 #[cfg(test)]
 #[path = "audio_test.rs"]
 mod tests;
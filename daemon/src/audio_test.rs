@@ -218,3 +218,138 @@ fn test_audio_capture_receives_samples() {
 
     capture.stop();
 }
+
+#[test]
+fn test_scripted_source_yields_blocks_in_order() {
+    let mut source = ScriptedSource::new(vec![vec![0.1, 0.2], vec![0.3]], 16000);
+
+    assert_eq!(source.sample_rate(), 16000);
+    assert!(!source.is_finished());
+    assert_eq!(source.try_recv(), Some(vec![0.1, 0.2]));
+    assert_eq!(source.try_recv(), Some(vec![0.3]));
+    assert!(source.is_finished());
+    assert_eq!(source.try_recv(), None);
+    assert_eq!(source.poll_error(), None);
+}
+
+#[test]
+fn test_scripted_source_from_samples_splits_into_100ms_blocks() {
+    let samples = vec![0.0; 4000];
+    let mut source = ScriptedSource::from_samples(&samples, 16000);
+
+    let mut block_lens = Vec::new();
+    while let Some(block) = source.try_recv() {
+        block_lens.push(block.len());
+    }
+    assert_eq!(block_lens, vec![1600, 1600, 800]);
+}
+
+#[test]
+fn test_decode_pcm_s16le() {
+    let bytes = [0x00, 0x00, 0x00, 0x40, 0x00, 0x80, 0xFF];
+    let samples = decode_pcm(&bytes, PcmFormat::S16le);
+
+    // Trailing odd byte is ignored
+    assert_eq!(samples, vec![0.0, 0.5, -1.0]);
+}
+
+#[test]
+fn test_decode_pcm_f32le() {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&0.25f32.to_le_bytes());
+    bytes.extend_from_slice(&(-0.75f32).to_le_bytes());
+
+    assert_eq!(decode_pcm(&bytes, PcmFormat::F32le), vec![0.25, -0.75]);
+}
+
+#[test]
+fn test_file_source_reads_stereo_wav_as_mono() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("stereo.wav");
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: 8000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for _ in 0..1000 {
+        writer.write_sample(16384i16).unwrap();
+        writer.write_sample(0i16).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let mut source = FileSource::open(&path).unwrap();
+    assert_eq!(source.sample_rate(), 8000);
+
+    let mut samples = Vec::new();
+    while let Some(block) = source.try_recv() {
+        samples.extend(block);
+    }
+    assert!(source.is_finished());
+    assert_eq!(samples.len(), 1000);
+    assert!(samples.iter().all(|s| (s - 0.25).abs() < f32::EPSILON));
+}
+
+#[test]
+fn test_file_source_missing_file_returns_error() {
+    assert!(FileSource::open("/nonexistent/recording.wav").is_err());
+}
+
+#[test]
+fn test_open_source_file_requires_path() {
+    let config = AudioConfig {
+        source: AudioSourceKind::File,
+        path: None,
+        ..AudioConfig::default()
+    };
+
+    let result = open_source(&config);
+    assert!(result.is_err());
+}
+
+fn test_devices() -> Vec<InputDeviceInfo> {
+    vec![
+        InputDeviceInfo {
+            id: "CoreAudio:BuiltInMic".to_string(),
+            name: "MacBook Pro Microphone".to_string(),
+            is_default: true,
+        },
+        InputDeviceInfo {
+            id: "CoreAudio:Jabra-1234".to_string(),
+            name: "Jabra Evolve2 65".to_string(),
+            is_default: false,
+        },
+        InputDeviceInfo {
+            id: "CoreAudio:Jabra-5678".to_string(),
+            name: "Jabra".to_string(),
+            is_default: false,
+        },
+    ]
+}
+
+#[test]
+fn test_match_device_by_id() {
+    let devices = test_devices();
+    assert_eq!(match_device(&devices, "CoreAudio:Jabra-1234"), Some(1));
+}
+
+#[test]
+fn test_match_device_exact_name_beats_substring() {
+    let devices = test_devices();
+    assert_eq!(match_device(&devices, "jabra"), Some(2));
+}
+
+#[test]
+fn test_match_device_by_name_substring() {
+    let devices = test_devices();
+    assert_eq!(match_device(&devices, "evolve2"), Some(1));
+    assert_eq!(match_device(&devices, "MacBook"), Some(0));
+}
+
+#[test]
+fn test_match_device_missing() {
+    let devices = test_devices();
+    assert_eq!(match_device(&devices, "Blue Yeti"), None);
+    assert_eq!(match_device(&[], "anything"), None);
+}
@@ -2,16 +2,28 @@
 //!
 //! Handles loading, saving, and providing defaults for the daemon configuration.
 
+use crate::transcribe::{DecodingOptions, Vocabulary};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Main configuration struct for the daemon.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
     pub model: ModelConfig,
+    pub audio: AudioConfig,
+    pub vad: VadConfig,
     pub latency: LatencyConfig,
+    pub whisper: WhisperConfig,
+    pub filter: FilterConfig,
+    pub postprocess: PostprocessConfig,
+    pub http: HttpConfig,
+    pub process: ProcessConfig,
+    pub streaming: StreamingConfig,
+    pub vocabulary: VocabularyConfig,
     pub injection: InjectionConfig,
     pub logging: LoggingConfig,
     pub gui: GuiConfig,
@@ -24,6 +36,8 @@ pub struct Config {
 pub struct DaemonConfig {
     /// State to enter after model initialization completes.
     pub initial_state: InitialState,
+    /// Unload the models after this many seconds paused; 0 keeps them loaded.
+    pub idle_unload_secs: u64,
 }
 
 /// State the daemon should enter after initialization.
@@ -41,11 +55,127 @@ pub enum InitialState {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct ModelConfig {
+    /// What transcribes speech.
+    pub backend: Backend,
     /// Speech recognition model to use.
     pub model: SpeechModel,
     /// Language for transcription. Use "auto" for automatic detection,
     /// or a specific language (e.g. "en", "english", "sk", "slovak").
     pub language: String,
+    /// Languages auto-detection may choose from. Empty means `gui.languages`,
+    /// or any language if that is empty too.
+    pub candidate_languages: Vec<String>,
+    /// Whether to type what was said or its English translation.
+    pub task: Task,
+}
+
+/// Transcription backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// whisper.cpp running `model` in the daemon.
+    #[default]
+    Whisper,
+    /// An OpenAI-compatible transcription server, configured in `[http]`.
+    Http,
+    /// A helper program speaking the daemon's pipe protocol, configured in
+    /// `[process]`.
+    Process,
+}
+
+/// What the model produces from speech.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Task {
+    /// Text in the spoken language.
+    #[default]
+    Transcribe,
+    /// English translation of the speech.
+    Translate,
+}
+
+/// Audio input configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioConfig {
+    /// Where the engine reads audio from.
+    pub source: AudioSourceKind,
+    /// Input device to capture from, by stable id or name. Unset uses the system default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    /// What to do when the configured device is not connected.
+    pub device_fallback: DeviceFallback,
+    /// Path to a WAV or FLAC file, used when `source = "file"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Sample format of raw PCM read from stdin.
+    pub stdin_format: PcmFormat,
+    /// Sample rate of raw PCM read from stdin, in Hz.
+    pub stdin_sample_rate: u32,
+    /// Number of interleaved channels in raw PCM read from stdin.
+    pub stdin_channels: u16,
+}
+
+/// Kind of audio source feeding the engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioSourceKind {
+    /// Capture from the system input device.
+    #[default]
+    Microphone,
+    /// Replay a WAV or FLAC file.
+    File,
+    /// Read raw PCM from standard input.
+    Stdin,
+}
+
+/// Policy when the configured input device cannot be found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceFallback {
+    /// Log a warning and capture from the system default device.
+    #[default]
+    Default,
+    /// Refuse to start listening.
+    Fail,
+}
+
+/// Raw PCM sample encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PcmFormat {
+    /// Signed 16-bit little-endian.
+    #[default]
+    S16le,
+    /// 32-bit float little-endian.
+    F32le,
+}
+
+/// Speech detection configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VadConfig {
+    /// Speech probability (0.0-1.0) at which speech starts.
+    pub threshold: f32,
+    /// Probability below which ongoing speech counts as silence.
+    /// Must not exceed `threshold`.
+    pub end_threshold: f32,
+    /// Speech must last this long before an utterance starts, in milliseconds.
+    pub min_speech_ms: u32,
+    /// Silence must last this long before an utterance ends, in milliseconds.
+    /// Unset uses the value for the `[latency]` mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_silence_ms: Option<u32>,
+    /// Samples per VAD inference at 16kHz: 512, 1024 or 1536.
+    pub chunk_size: usize,
+    /// Longer utterances are split at a pause, in seconds.
+    /// Unset uses the value for the `[latency]` mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_segment_secs: Option<f32>,
+    /// Audio kept from before detected speech start, in milliseconds.
+    pub pre_roll_ms: u32,
+    /// Audio kept after the last voiced chunk, in milliseconds.
+    pub post_roll_ms: u32,
 }
 
 /// Latency/accuracy trade-off configuration.
@@ -54,7 +184,8 @@ pub struct ModelConfig {
 pub struct LatencyConfig {
     /// Latency mode: "fast", "balanced", or "accurate".
     pub mode: LatencyMode,
-    /// Minimum chunk duration in seconds before transcription.
+    /// Minimum audio duration in seconds sent to the model; shorter
+    /// utterances are padded with silence.
     pub min_chunk_seconds: f32,
 }
 
@@ -68,6 +199,184 @@ pub enum LatencyMode {
     Accurate,
 }
 
+/// Pipeline settings a latency mode stands for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyProfile {
+    /// Silence that ends an utterance, in milliseconds, unless `[vad] min_silence_ms` is set.
+    pub min_silence_ms: u32,
+    /// Utterances are cut at this length so long dictation is transcribed in pieces.
+    pub max_segment_secs: f32,
+    /// Utterances with less speech than this are dropped as noise.
+    pub min_speech_secs: f32,
+    /// Whisper beam search width; 1 decodes greedily.
+    pub beam_size: u32,
+}
+
+impl LatencyMode {
+    /// Concrete pipeline settings for this mode.
+    pub fn profile(self) -> LatencyProfile {
+        match self {
+            LatencyMode::Fast => LatencyProfile {
+                min_silence_ms: 160,
+                max_segment_secs: 8.0,
+                min_speech_secs: 0.2,
+                beam_size: 1,
+            },
+            LatencyMode::Balanced => LatencyProfile {
+                min_silence_ms: 256,
+                max_segment_secs: 15.0,
+                min_speech_secs: 0.3,
+                beam_size: 1,
+            },
+            LatencyMode::Accurate => LatencyProfile {
+                min_silence_ms: 480,
+                max_segment_secs: 28.0,
+                min_speech_secs: 0.4,
+                beam_size: 5,
+            },
+        }
+    }
+}
+
+/// Whisper decoding parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WhisperConfig {
+    /// Beam search width; 1 decodes greedily.
+    /// Unset uses the value for the `[latency]` mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beam_size: Option<u32>,
+    /// Candidates sampled at each fallback temperature when decoding greedily.
+    pub best_of: u32,
+    /// Sampling temperature of the first decoding attempt.
+    pub temperature: f32,
+    /// Temperature added for each retry after a failed attempt; 0 disables retries.
+    pub temperature_increment: f32,
+    /// CPU threads used for inference. Unset uses up to 4.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threads: Option<u32>,
+    /// Segments more likely than this to be silence are dropped.
+    pub no_speech_threshold: f32,
+    /// Attempts whose token entropy falls below this, i.e. repetitive text,
+    /// are retried.
+    pub entropy_threshold: f32,
+    /// Attempts with a lower average token log-probability are retried.
+    pub logprob_threshold: f32,
+    /// Keep the model from starting a segment with blank output.
+    pub suppress_blank: bool,
+    /// Keep the model from emitting non-speech tokens such as `[MUSIC]`.
+    pub suppress_non_speech: bool,
+    /// Decode each utterance as a single segment, which is faster.
+    pub single_segment: bool,
+}
+
+/// Removal of text the model made up rather than heard.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterConfig {
+    /// Segments consisting of just one of these phrases are dropped.
+    /// Case and punctuation are ignored.
+    pub blocklist: Vec<String>,
+    /// A phrase repeated more often in a row is cut to one occurrence; 0 disables.
+    pub max_repeats: u32,
+    /// Transcripts with more non-space characters per second of audio are
+    /// dropped; 0 disables.
+    pub max_chars_per_sec: f32,
+}
+
+/// Clean-up applied to transcribed text before it is typed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostprocessConfig {
+    /// Stages to run, in order.
+    pub stages: Vec<PostprocessStage>,
+    /// Spoken phrases and what to type instead, for the `replacements`
+    /// stage. Matched case-insensitively on whole words.
+    pub replacements: BTreeMap<String, String>,
+    /// Words and phrases the `filter_words` stage removes, e.g. fillers.
+    pub filter_words: Vec<String>,
+    /// Where punctuation comes from.
+    pub punctuation: PunctuationMode,
+}
+
+/// Source of punctuation in dictated text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PunctuationMode {
+    /// Keep the punctuation the model adds; spoken commands add more.
+    #[default]
+    Model,
+    /// Drop the model's punctuation so only spoken commands punctuate.
+    /// Needs the `punctuation_commands` stage.
+    Spoken,
+}
+
+/// A step of text post-processing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostprocessStage {
+    /// Collapse runs of spaces and remove spaces before punctuation.
+    Whitespace,
+    /// Capitalize the first word and words starting a sentence.
+    Capitalization,
+    /// Replace phrases from `postprocess.replacements`.
+    Replacements,
+    /// Turn spoken punctuation ("comma", "new line") into symbols.
+    PunctuationCommands,
+    /// Remove phrases from `postprocess.filter_words`.
+    FilterWords,
+}
+
+/// OpenAI-compatible transcription server, used with `model.backend = "http"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Server base URL; audio is posted to `{url}/v1/audio/transcriptions`.
+    pub url: String,
+    /// Model name sent with each request.
+    pub model: String,
+    /// Bearer token, if the server requires one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// How long to wait for a transcription, in milliseconds.
+    pub timeout_ms: u32,
+    /// Transcribe with the local `model.model` when the server fails.
+    pub fallback: bool,
+}
+
+/// Transcription helper program, used with `model.backend = "process"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessConfig {
+    /// Program to run, looked up in `PATH` unless it is a path.
+    pub command: String,
+    /// Arguments passed to the program.
+    pub args: Vec<String>,
+    /// How long to wait for a transcription before restarting the program,
+    /// in milliseconds.
+    pub timeout_ms: u32,
+}
+
+/// Phrases Whisper is known to produce on silence, mostly subtitle credits.
+const DEFAULT_BLOCKLIST: &[&str] = &[
+    "Thank you for watching.",
+    "Thanks for watching!",
+    "Thank you for watching, please subscribe.",
+    "Please subscribe to the channel.",
+    "Like and subscribe.",
+    "See you in the next video.",
+    "Subtitles by the Amara.org community",
+    "Transcription by CastingWords",
+    "Titulky vytvořil JohnyX",
+    "Titulky vytvoril JohnyX",
+    "Untertitel im Auftrag des ZDF, 2017",
+    "Untertitel der Amara.org-Community",
+    "Sous-titrage Société Radio-Canada",
+    "Sottotitoli creati dalla comunità Amara.org",
+    "Субтитры сделал DimaTorzok",
+    "Продолжение следует...",
+];
+
 /// Supported speech recognition models.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
@@ -86,13 +395,80 @@ pub enum SpeechModel {
     WhisperLargeV3Turbo,
 }
 
-/// Configuration for keystroke injection behavior.
+impl SpeechModel {
+    /// Whether the model only knows English, so it cannot detect languages
+    /// or translate.
+    pub fn is_english_only(self) -> bool {
+        matches!(
+            self,
+            SpeechModel::WhisperTinyEn
+                | SpeechModel::WhisperBaseEn
+                | SpeechModel::WhisperSmallEn
+                | SpeechModel::WhisperMediumEn
+        )
+    }
+}
+
+impl std::str::FromStr for SpeechModel {
+    type Err = anyhow::Error;
+
+    /// Parse a model name as written in `[model] model`, e.g. `"whisper-small"`.
+    fn from_str(name: &str) -> Result<Self> {
+        use serde::de::IntoDeserializer;
+        let deserializer: serde::de::value::StrDeserializer<'_, serde::de::value::Error> =
+            name.into_deserializer();
+        Self::deserialize(deserializer).map_err(|_| anyhow::anyhow!("Unknown model \"{name}\""))
+    }
+}
+
+/// Partial transcriptions while an utterance is still in progress.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamingConfig {
+    /// Re-transcribe the utterance in progress and broadcast partial results.
+    pub enabled: bool,
+    /// Audio between partial transcriptions, in milliseconds.
+    pub interval_ms: u32,
+    /// Inject words once consecutive partials agree on them, instead of
+    /// waiting for the final transcription.
+    pub inject_stable_prefix: bool,
+}
+
+/// Terms and context that bias transcription toward the words you use.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VocabularyConfig {
+    /// Words to spell as written, e.g. product names and jargon.
+    pub terms: Vec<String>,
+    /// Free-form text the model reads before each utterance.
+    pub prompt: Option<String>,
+    /// Additional terms and prompts by language code.
+    pub languages: BTreeMap<String, VocabularyEntry>,
+    /// Additional terms and prompts by application, matched like the
+    /// injection allowlist.
+    pub apps: BTreeMap<String, VocabularyEntry>,
+}
+
+/// Terms and prompt for one language or application.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
+pub struct VocabularyEntry {
+    pub terms: Vec<String>,
+    /// Replaces the less specific prompt. Application prompts take
+    /// precedence over language prompts.
+    pub prompt: Option<String>,
+}
+
+/// Configuration for keystroke injection behavior.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct InjectionConfig {
     /// List of application names to inject into. Empty means inject into all apps.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub allowlist: Vec<String>,
+    /// An utterance continues the text typed before it in the same app
+    /// within this many seconds; 0 types every utterance as is.
+    pub context_reset_secs: u32,
 }
 
 /// Logging configuration.
@@ -139,8 +515,496 @@ impl LogLevel {
 impl Default for ModelConfig {
     fn default() -> Self {
         Self {
+            backend: Backend::default(),
             model: SpeechModel::default(),
             language: "auto".to_string(),
+            candidate_languages: Vec::new(),
+            task: Task::default(),
+        }
+    }
+}
+
+impl ModelConfig {
+    /// Code of the configured language, e.g. `"sk"` for `"slovak"`, or
+    /// `None` for auto-detection.
+    pub fn language_code(&self) -> Option<String> {
+        if self.language == "auto" {
+            return None;
+        }
+        crate::transcribe::language_code(&self.language).map(str::to_string)
+    }
+
+    /// Check that the languages are known to Whisper and that the model
+    /// supports the task.
+    pub fn validate(&self) -> Result<()> {
+        if self.language != "auto" && crate::transcribe::language_code(&self.language).is_none() {
+            anyhow::bail!("model.language: unknown language {:?}", self.language);
+        }
+        for language in &self.candidate_languages {
+            if crate::transcribe::language_code(language).is_none() {
+                anyhow::bail!("model.candidate_languages: unknown language {language:?}");
+            }
+        }
+        if self.task == Task::Translate && self.model.is_english_only() {
+            anyhow::bail!(
+                "model.task = \"translate\" needs a multilingual model, not an English-only one"
+            );
+        }
+        Ok(())
+    }
+}
+
+impl VocabularyConfig {
+    /// Check that language keys are known to Whisper.
+    pub fn validate(&self) -> Result<()> {
+        for language in self.languages.keys() {
+            if crate::transcribe::language_code(language).is_none() {
+                anyhow::bail!("vocabulary.languages: unknown language {language:?}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve the prompts that apply while `app` is in the foreground.
+    pub fn resolve(&self, app: Option<&str>) -> Vocabulary {
+        let app_lower = app.map(str::to_lowercase);
+        let app_entries: Vec<&VocabularyEntry> = self
+            .apps
+            .iter()
+            .filter(|(name, _)| {
+                app_lower
+                    .as_deref()
+                    .is_some_and(|app| app.contains(&name.to_lowercase()))
+            })
+            .map(|(_, entry)| entry)
+            .collect();
+        let app_prompt = app_entries
+            .iter()
+            .rev()
+            .find_map(|entry| entry.prompt.as_deref());
+        let app_terms = app_entries.iter().flat_map(|entry| &entry.terms);
+
+        let mut base_terms: Vec<&String> = self.terms.iter().collect();
+        base_terms.extend(app_terms);
+
+        let languages = self
+            .languages
+            .iter()
+            .filter_map(|(language, entry)| {
+                let code = crate::transcribe::language_code(language)?;
+                let prompt = app_prompt
+                    .or(entry.prompt.as_deref())
+                    .or(self.prompt.as_deref());
+                let mut terms = base_terms.clone();
+                terms.extend(&entry.terms);
+                Some((code.to_string(), prompt_text(prompt, &terms)?))
+            })
+            .collect();
+
+        Vocabulary {
+            prompt: prompt_text(app_prompt.or(self.prompt.as_deref()), &base_terms),
+            languages,
+        }
+    }
+
+    /// Add `terms` for all languages, or only for `language` if given.
+    ///
+    /// Blank and already present terms are skipped. Returns how many were added.
+    pub fn add_terms(&mut self, terms: &[String], language: Option<&str>) -> Result<usize> {
+        let list = match language {
+            Some(language) => {
+                let code = crate::transcribe::language_code(language)
+                    .with_context(|| format!("Unknown language {language:?}"))?;
+                &mut self.languages.entry(code.to_string()).or_default().terms
+            }
+            None => &mut self.terms,
+        };
+        let mut added = 0;
+        for term in terms.iter().map(|term| term.trim()) {
+            if !term.is_empty() && !list.iter().any(|t| t == term) {
+                list.push(term.to_string());
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+}
+
+/// Combine a prompt and terms into Whisper's initial prompt text.
+fn prompt_text(prompt: Option<&str>, terms: &[&String]) -> Option<String> {
+    let mut unique: Vec<&str> = Vec::new();
+    for term in terms.iter().map(|term| term.trim()) {
+        if !term.is_empty() && !unique.contains(&term) {
+            unique.push(term);
+        }
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    if let Some(prompt) = prompt.map(str::trim).filter(|p| !p.is_empty()) {
+        parts.push(prompt.to_string());
+    }
+    if !unique.is_empty() {
+        parts.push(format!("{}.", unique.join(", ")));
+    }
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            source: AudioSourceKind::Microphone,
+            device: None,
+            device_fallback: DeviceFallback::Default,
+            path: None,
+            stdin_format: PcmFormat::S16le,
+            stdin_sample_rate: 16000,
+            stdin_channels: 1,
+        }
+    }
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            threshold: crate::vad::DEFAULT_THRESHOLD,
+            end_threshold: crate::vad::DEFAULT_END_THRESHOLD,
+            min_speech_ms: 64,
+            min_silence_ms: None,
+            chunk_size: 512,
+            max_segment_secs: None,
+            pre_roll_ms: 300,
+            post_roll_ms: 250,
+        }
+    }
+}
+
+impl VadConfig {
+    /// Smallest accepted `max_segment_secs`.
+    const MIN_SEGMENT_LIMIT_SECS: f32 = 1.0;
+
+    /// Maximum utterance length, from `max_segment_secs` or the latency mode.
+    pub fn max_segment_secs(&self, mode: LatencyMode) -> f32 {
+        self.max_segment_secs
+            .unwrap_or(mode.profile().max_segment_secs)
+    }
+
+    /// Check that values are in range.
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.threshold) {
+            anyhow::bail!(
+                "vad.threshold must be between 0.0 and 1.0, got {}",
+                self.threshold
+            );
+        }
+        if !(0.0..=self.threshold).contains(&self.end_threshold) {
+            anyhow::bail!(
+                "vad.end_threshold must be between 0.0 and vad.threshold ({}), got {}",
+                self.threshold,
+                self.end_threshold
+            );
+        }
+        if let Some(max) = self.max_segment_secs
+            && !(Self::MIN_SEGMENT_LIMIT_SECS..=LatencyConfig::MAX_CHUNK_SECONDS).contains(&max)
+        {
+            anyhow::bail!(
+                "vad.max_segment_secs must be between {} and {}, got {}",
+                Self::MIN_SEGMENT_LIMIT_SECS,
+                LatencyConfig::MAX_CHUNK_SECONDS,
+                max
+            );
+        }
+        if !crate::vad::VAD_CHUNK_SIZES.contains(&self.chunk_size) {
+            anyhow::bail!(
+                "vad.chunk_size must be one of {:?}, got {}",
+                crate::vad::VAD_CHUNK_SIZES,
+                self.chunk_size
+            );
+        }
+        Ok(())
+    }
+
+    /// State machine settings with millisecond durations converted to chunks.
+    ///
+    /// `mode` supplies the silence timeout when `min_silence_ms` is unset.
+    pub fn detector_config(&self, mode: LatencyMode) -> crate::vad::VadConfig {
+        let chunks = |ms: u32| {
+            crate::segment::ms_to_samples(ms)
+                .div_ceil(self.chunk_size)
+                .max(1)
+        };
+        crate::vad::VadConfig {
+            threshold: self.threshold,
+            end_threshold: self.end_threshold,
+            min_speech_chunks: chunks(self.min_speech_ms),
+            min_silence_chunks: chunks(
+                self.min_silence_ms.unwrap_or(mode.profile().min_silence_ms),
+            ),
+        }
+    }
+}
+
+impl LatencyConfig {
+    /// Longest audio Whisper accepts in one pass.
+    const MAX_CHUNK_SECONDS: f32 = 30.0;
+
+    /// Check that values are in range.
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=Self::MAX_CHUNK_SECONDS).contains(&self.min_chunk_seconds) {
+            anyhow::bail!(
+                "latency.min_chunk_seconds must be between 0 and {}, got {}",
+                Self::MAX_CHUNK_SECONDS,
+                self.min_chunk_seconds
+            );
+        }
+        Ok(())
+    }
+}
+
+impl StreamingConfig {
+    /// Shortest accepted `interval_ms`.
+    const MIN_INTERVAL_MS: u32 = 100;
+
+    /// Check that values are in range.
+    pub fn validate(&self) -> Result<()> {
+        if self.interval_ms < Self::MIN_INTERVAL_MS {
+            anyhow::bail!(
+                "streaming.interval_ms must be at least {}, got {}",
+                Self::MIN_INTERVAL_MS,
+                self.interval_ms
+            );
+        }
+        Ok(())
+    }
+}
+
+impl WhisperConfig {
+    /// Most beams and candidates whisper.cpp decodes in parallel.
+    const MAX_DECODERS: u32 = 8;
+
+    /// Decoding options with the beam width from `beam_size` or the latency mode.
+    pub fn decoding(&self, mode: LatencyMode) -> DecodingOptions {
+        DecodingOptions {
+            beam_size: self.beam_size.unwrap_or(mode.profile().beam_size),
+            best_of: self.best_of,
+            temperature: self.temperature,
+            temperature_increment: self.temperature_increment,
+            threads: self.threads,
+            no_speech_threshold: self.no_speech_threshold,
+            entropy_threshold: self.entropy_threshold,
+            logprob_threshold: self.logprob_threshold,
+            suppress_blank: self.suppress_blank,
+            suppress_non_speech: self.suppress_non_speech,
+            single_segment: self.single_segment,
+            suppress_punctuation: false,
+        }
+    }
+
+    /// Check that values are in range.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(beam_size) = self.beam_size
+            && !(1..=Self::MAX_DECODERS).contains(&beam_size)
+        {
+            anyhow::bail!(
+                "whisper.beam_size must be between 1 and {}, got {}",
+                Self::MAX_DECODERS,
+                beam_size
+            );
+        }
+        if !(1..=Self::MAX_DECODERS).contains(&self.best_of) {
+            anyhow::bail!(
+                "whisper.best_of must be between 1 and {}, got {}",
+                Self::MAX_DECODERS,
+                self.best_of
+            );
+        }
+        if !(0.0..=1.0).contains(&self.temperature) {
+            anyhow::bail!(
+                "whisper.temperature must be between 0.0 and 1.0, got {}",
+                self.temperature
+            );
+        }
+        if !(0.0..=1.0).contains(&self.temperature_increment) {
+            anyhow::bail!(
+                "whisper.temperature_increment must be between 0.0 and 1.0, got {}",
+                self.temperature_increment
+            );
+        }
+        if self.threads == Some(0) {
+            anyhow::bail!("whisper.threads must be at least 1");
+        }
+        if !(0.0..=1.0).contains(&self.no_speech_threshold) {
+            anyhow::bail!(
+                "whisper.no_speech_threshold must be between 0.0 and 1.0, got {}",
+                self.no_speech_threshold
+            );
+        }
+        if self.entropy_threshold <= 0.0 {
+            anyhow::bail!(
+                "whisper.entropy_threshold must be positive, got {}",
+                self.entropy_threshold
+            );
+        }
+        if self.logprob_threshold > 0.0 {
+            anyhow::bail!(
+                "whisper.logprob_threshold must not be positive, got {}",
+                self.logprob_threshold
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Default for WhisperConfig {
+    fn default() -> Self {
+        let decoding = DecodingOptions::default();
+        Self {
+            beam_size: None,
+            best_of: decoding.best_of,
+            temperature: decoding.temperature,
+            temperature_increment: decoding.temperature_increment,
+            threads: decoding.threads,
+            no_speech_threshold: decoding.no_speech_threshold,
+            entropy_threshold: decoding.entropy_threshold,
+            logprob_threshold: decoding.logprob_threshold,
+            suppress_blank: decoding.suppress_blank,
+            suppress_non_speech: decoding.suppress_non_speech,
+            single_segment: decoding.single_segment,
+        }
+    }
+}
+
+impl FilterConfig {
+    /// Check that values are in range.
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..).contains(&self.max_chars_per_sec) {
+            anyhow::bail!(
+                "filter.max_chars_per_sec must not be negative, got {}",
+                self.max_chars_per_sec
+            );
+        }
+        Ok(())
+    }
+}
+
+impl PostprocessConfig {
+    /// Check that phrases to match are not blank.
+    pub fn validate(&self) -> Result<()> {
+        if self
+            .replacements
+            .keys()
+            .any(|phrase| phrase.trim().is_empty())
+        {
+            anyhow::bail!("postprocess.replacements: phrases must not be empty");
+        }
+        if self.filter_words.iter().any(|word| word.trim().is_empty()) {
+            anyhow::bail!("postprocess.filter_words: entries must not be empty");
+        }
+        if self.punctuation == PunctuationMode::Spoken
+            && !self.stages.contains(&PostprocessStage::PunctuationCommands)
+        {
+            anyhow::bail!(
+                "postprocess.punctuation = \"spoken\" needs the punctuation_commands stage"
+            );
+        }
+        Ok(())
+    }
+}
+
+impl HttpConfig {
+    /// Check that the URL and timeout are usable.
+    pub fn validate(&self) -> Result<()> {
+        if !(self.url.starts_with("http://") || self.url.starts_with("https://")) {
+            anyhow::bail!(
+                "http.url must start with http:// or https://, got {:?}",
+                self.url
+            );
+        }
+        if self.timeout_ms == 0 {
+            anyhow::bail!("http.timeout_ms must be greater than 0");
+        }
+        Ok(())
+    }
+}
+
+impl ProcessConfig {
+    /// Check that the timeout is usable.
+    pub fn validate(&self) -> Result<()> {
+        if self.timeout_ms == 0 {
+            anyhow::bail!("process.timeout_ms must be greater than 0");
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProcessConfig {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            args: Vec::new(),
+            timeout_ms: 10_000,
+        }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://127.0.0.1:8000".to_string(),
+            model: "whisper-1".to_string(),
+            api_key: None,
+            timeout_ms: 10_000,
+            fallback: true,
+        }
+    }
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self {
+            blocklist: DEFAULT_BLOCKLIST.iter().map(|s| s.to_string()).collect(),
+            max_repeats: 4,
+            max_chars_per_sec: 30.0,
+        }
+    }
+}
+
+impl InjectionConfig {
+    /// How long an utterance may follow the previous one and continue it.
+    pub fn context_reset(&self) -> Duration {
+        Duration::from_secs(self.context_reset_secs.into())
+    }
+}
+
+impl Default for InjectionConfig {
+    fn default() -> Self {
+        Self {
+            allowlist: Vec::new(),
+            context_reset_secs: 30,
+        }
+    }
+}
+
+impl Default for PostprocessConfig {
+    fn default() -> Self {
+        Self {
+            stages: vec![
+                PostprocessStage::FilterWords,
+                PostprocessStage::Replacements,
+                PostprocessStage::Whitespace,
+            ],
+            replacements: BTreeMap::new(),
+            filter_words: Vec::new(),
+            punctuation: PunctuationMode::Model,
+        }
+    }
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_ms: 500,
+            inject_stable_prefix: false,
         }
     }
 }
@@ -200,9 +1064,61 @@ impl Config {
         Self::parse(&content)
     }
 
-    /// Parse configuration from a TOML string.
+    /// Language codes auto-detection is restricted to: `model.candidate_languages`,
+    /// or `gui.languages` if that is empty. Unknown languages are skipped.
+    pub fn candidate_languages(&self) -> Vec<String> {
+        let languages = if self.model.candidate_languages.is_empty() {
+            &self.gui.languages
+        } else {
+            &self.model.candidate_languages
+        };
+        let mut codes: Vec<String> = Vec::new();
+        for code in languages
+            .iter()
+            .filter_map(|language| crate::transcribe::language_code(language))
+        {
+            if !codes.iter().any(|c| c == code) {
+                codes.push(code.to_string());
+            }
+        }
+        codes
+    }
+
+    /// Parse configuration from a TOML string and validate it.
     pub fn parse(content: &str) -> Result<Self> {
-        toml::from_str(content).context("Failed to parse config file as TOML")
+        let config: Self =
+            toml::from_str(content).context("Failed to parse config file as TOML")?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that all values are in range.
+    pub fn validate(&self) -> Result<()> {
+        self.model.validate().context("Invalid [model] section")?;
+        self.vocabulary
+            .validate()
+            .context("Invalid [vocabulary] section")?;
+        self.vad.validate().context("Invalid [vad] section")?;
+        self.latency
+            .validate()
+            .context("Invalid [latency] section")?;
+        self.whisper
+            .validate()
+            .context("Invalid [whisper] section")?;
+        self.filter.validate().context("Invalid [filter] section")?;
+        self.postprocess
+            .validate()
+            .context("Invalid [postprocess] section")?;
+        self.http.validate().context("Invalid [http] section")?;
+        self.process
+            .validate()
+            .context("Invalid [process] section")?;
+        if self.model.backend == Backend::Process && self.process.command.trim().is_empty() {
+            anyhow::bail!("process.command must be set when model.backend = \"process\"");
+        }
+        self.streaming
+            .validate()
+            .context("Invalid [streaming] section")
     }
 
     /// Save configuration to the default path.
@@ -211,6 +1127,11 @@ impl Config {
         self.save_to(&path)
     }
 
+    /// Serialize the configuration as it is written to the config file.
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).context("Failed to serialize config to TOML")
+    }
+
     /// Save configuration to a specific path.
     pub fn save_to(&self, path: impl AsRef<Path>) -> Result<()> {
         let path = path.as_ref();
@@ -220,7 +1141,7 @@ impl Config {
             })?;
         }
 
-        let content = toml::to_string_pretty(self).context("Failed to serialize config to TOML")?;
+        let content = self.to_toml()?;
 
         std::fs::write(path, content)
             .with_context(|| format!("Failed to write config file: {}", path.display()))?;
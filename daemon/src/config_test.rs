@@ -13,6 +13,46 @@ fn test_default_config_values() {
     assert_eq!(config.latency.mode, LatencyMode::Balanced);
     assert!((config.latency.min_chunk_seconds - 1.0).abs() < f32::EPSILON);
 
+    // Audio defaults
+    assert_eq!(config.audio.source, AudioSourceKind::Microphone);
+    assert!(config.audio.path.is_none());
+    assert_eq!(config.audio.stdin_format, PcmFormat::S16le);
+    assert_eq!(config.audio.stdin_sample_rate, 16000);
+    assert_eq!(config.audio.stdin_channels, 1);
+
+    // VAD defaults
+    assert!((config.vad.threshold - 0.5).abs() < f32::EPSILON);
+    assert!((config.vad.end_threshold - 0.35).abs() < f32::EPSILON);
+    assert_eq!(config.vad.min_speech_ms, 64);
+    assert_eq!(config.vad.min_silence_ms, None);
+    assert_eq!(config.vad.chunk_size, 512);
+    assert_eq!(config.vad.max_segment_secs, None);
+    assert_eq!(config.vad.pre_roll_ms, 300);
+    assert_eq!(config.vad.post_roll_ms, 250);
+
+    // Streaming defaults
+    assert!(!config.streaming.enabled);
+    assert_eq!(config.streaming.interval_ms, 500);
+    assert!(!config.streaming.inject_stable_prefix);
+
+    // Whisper defaults
+    assert_eq!(config.whisper.beam_size, None);
+    assert_eq!(config.whisper.best_of, 1);
+    assert_eq!(config.whisper.threads, None);
+    assert!((config.whisper.no_speech_threshold - 0.6).abs() < f32::EPSILON);
+    assert!(config.whisper.suppress_non_speech);
+    assert!(config.whisper.single_segment);
+
+    // Filter defaults
+    assert!(
+        config
+            .filter
+            .blocklist
+            .contains(&"Thank you for watching.".to_string())
+    );
+    assert_eq!(config.filter.max_repeats, 4);
+    assert!((config.filter.max_chars_per_sec - 30.0).abs() < f32::EPSILON);
+
     // Injection defaults
     assert!(config.injection.allowlist.is_empty());
 
@@ -126,15 +166,83 @@ fn test_save_and_load_roundtrip() {
 
     let original = Config {
         model: ModelConfig {
+            backend: Backend::Http,
             model: SpeechModel::WhisperMedium,
             language: "cs".to_string(),
+            candidate_languages: vec!["cs".to_string(), "en".to_string()],
+            task: Task::Translate,
+        },
+        audio: AudioConfig {
+            source: AudioSourceKind::File,
+            path: Some(PathBuf::from("/tmp/session.wav")),
+            ..AudioConfig::default()
+        },
+        vad: VadConfig {
+            threshold: 0.6,
+            end_threshold: 0.4,
+            min_speech_ms: 96,
+            min_silence_ms: Some(500),
+            chunk_size: 1024,
+            max_segment_secs: Some(12.5),
+            pre_roll_ms: 500,
+            post_roll_ms: 100,
         },
         latency: LatencyConfig {
             mode: LatencyMode::Accurate,
             min_chunk_seconds: 2.0,
         },
+        whisper: WhisperConfig {
+            beam_size: Some(3),
+            temperature: 0.1,
+            threads: Some(2),
+            single_segment: false,
+            ..WhisperConfig::default()
+        },
+        filter: FilterConfig {
+            blocklist: vec!["Thanks for watching!".to_string()],
+            max_repeats: 2,
+            max_chars_per_sec: 0.0,
+        },
+        postprocess: PostprocessConfig {
+            stages: vec![
+                PostprocessStage::PunctuationCommands,
+                PostprocessStage::Capitalization,
+            ],
+            replacements: BTreeMap::from([("vee cee em".to_string(), "vcm".to_string())]),
+            filter_words: vec!["um".to_string()],
+            punctuation: PunctuationMode::Spoken,
+        },
+        http: HttpConfig {
+            url: "http://localhost:9000".to_string(),
+            api_key: Some("secret".to_string()),
+            fallback: false,
+            ..HttpConfig::default()
+        },
+        process: ProcessConfig {
+            command: "parakeet-server".to_string(),
+            args: vec!["--device".to_string(), "cuda".to_string()],
+            timeout_ms: 5000,
+        },
+        streaming: StreamingConfig {
+            enabled: true,
+            interval_ms: 300,
+            inject_stable_prefix: true,
+        },
+        vocabulary: VocabularyConfig {
+            terms: vec!["Kubernetes".to_string()],
+            prompt: Some("Meeting notes.".to_string()),
+            languages: BTreeMap::from([(
+                "cs".to_string(),
+                VocabularyEntry {
+                    terms: vec!["nasazení".to_string()],
+                    prompt: None,
+                },
+            )]),
+            apps: BTreeMap::new(),
+        },
         injection: InjectionConfig {
             allowlist: vec!["IntelliJ IDEA".to_string()],
+            context_reset_secs: 0,
         },
         logging: LoggingConfig {
             level: LogLevel::Debug,
@@ -144,6 +252,7 @@ fn test_save_and_load_roundtrip() {
         },
         daemon: DaemonConfig {
             initial_state: InitialState::Listening,
+            idle_unload_secs: 1800,
         },
     };
 
@@ -222,6 +331,13 @@ language = "slovak"
 
     let config = Config::parse(toml_content).unwrap();
     assert_eq!(config.model.language, "slovak");
+    assert_eq!(config.model.language_code().as_deref(), Some("sk"));
+}
+
+#[test]
+fn test_unknown_language_rejected() {
+    let err = Config::parse("[model]\nlanguage = \"klingon\"\n").unwrap_err();
+    assert!(format!("{err:#}").contains("klingon"));
 }
 
 #[test]
@@ -234,6 +350,112 @@ languages = ["en", "cs", "de"]
     assert_eq!(config.gui.languages, vec!["en", "cs", "de"]);
 }
 
+#[test]
+fn test_candidate_languages_fall_back_to_gui_languages() {
+    let toml = r#"
+[gui]
+languages = ["en", "Czech", "xx", "cs"]
+"#;
+    let config = Config::parse(toml).unwrap();
+    assert_eq!(config.candidate_languages(), vec!["en", "cs"]);
+}
+
+#[test]
+fn test_candidate_languages_override_gui_languages() {
+    let toml = r#"
+[model]
+candidate_languages = ["de", "french"]
+
+[gui]
+languages = ["en"]
+"#;
+    let config = Config::parse(toml).unwrap();
+    assert_eq!(config.candidate_languages(), vec!["de", "fr"]);
+}
+
+#[test]
+fn test_unknown_candidate_language_rejected() {
+    let err = Config::parse("[model]\ncandidate_languages = [\"klingon\"]\n").unwrap_err();
+    assert!(format!("{err:#}").contains("klingon"));
+}
+
+fn vocabulary_config() -> VocabularyConfig {
+    Config::parse(
+        r#"
+[vocabulary]
+terms = ["Kubernetes", "vcmctl"]
+prompt = "Notes from a software team."
+
+[vocabulary.languages.czech]
+terms = ["nasazení"]
+prompt = "Poznámky týmu."
+
+[vocabulary.apps."IntelliJ"]
+terms = ["Gradle", "vcmctl"]
+"#,
+    )
+    .unwrap()
+    .vocabulary
+}
+
+#[test]
+fn test_vocabulary_resolves_base_prompt() {
+    let vocabulary = vocabulary_config().resolve(None);
+    assert_eq!(
+        vocabulary.prompt_for(Some("en")),
+        Some("Notes from a software team. Kubernetes, vcmctl.")
+    );
+}
+
+#[test]
+fn test_vocabulary_language_entry_extends_terms_and_replaces_prompt() {
+    let vocabulary = vocabulary_config().resolve(None);
+    assert_eq!(
+        vocabulary.prompt_for(Some("cs")),
+        Some("Poznámky týmu. Kubernetes, vcmctl, nasazení.")
+    );
+}
+
+#[test]
+fn test_vocabulary_app_entry_matches_frontmost_app() {
+    let vocabulary = vocabulary_config().resolve(Some("IntelliJ IDEA"));
+    assert_eq!(
+        vocabulary.prompt_for(None),
+        Some("Notes from a software team. Kubernetes, vcmctl, Gradle.")
+    );
+
+    let vocabulary = vocabulary_config().resolve(Some("Terminal"));
+    assert_eq!(
+        vocabulary.prompt_for(None),
+        Some("Notes from a software team. Kubernetes, vcmctl.")
+    );
+}
+
+#[test]
+fn test_empty_vocabulary_has_no_prompt() {
+    let vocabulary = VocabularyConfig::default().resolve(Some("Terminal"));
+    assert_eq!(vocabulary.prompt_for(Some("en")), None);
+}
+
+#[test]
+fn test_vocabulary_add_terms_skips_duplicates() {
+    let mut vocabulary = vocabulary_config();
+    let terms = vec!["vcmctl".to_string(), " Tokio ".to_string(), "".to_string()];
+    assert_eq!(vocabulary.add_terms(&terms, None).unwrap(), 1);
+    assert_eq!(vocabulary.terms, vec!["Kubernetes", "vcmctl", "Tokio"]);
+
+    assert_eq!(vocabulary.add_terms(&terms, Some("German")).unwrap(), 2);
+    assert_eq!(vocabulary.languages["de"].terms, vec!["vcmctl", "Tokio"]);
+
+    assert!(vocabulary.add_terms(&terms, Some("klingon")).is_err());
+}
+
+#[test]
+fn test_unknown_vocabulary_language_rejected() {
+    let err = Config::parse("[vocabulary.languages.klingon]\nterms = [\"a\"]\n").unwrap_err();
+    assert!(format!("{err:#}").contains("klingon"));
+}
+
 #[test]
 fn gui_defaults_to_empty_languages() {
     let config: Config = toml::from_str("").unwrap();
@@ -255,3 +477,435 @@ initial_state = "paused"
     let config: Config = toml::from_str(toml).unwrap();
     assert_eq!(config.daemon.initial_state, InitialState::Paused);
 }
+
+#[test]
+fn test_daemon_idle_unload_disabled_by_default() {
+    let config: Config = toml::from_str("").unwrap();
+    assert_eq!(config.daemon.idle_unload_secs, 0);
+
+    let toml = r#"
+[daemon]
+idle_unload_secs = 900
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    assert_eq!(config.daemon.idle_unload_secs, 900);
+}
+
+#[test]
+fn test_audio_stdin_source_parsed() {
+    let toml = r#"
+[audio]
+source = "stdin"
+stdin_format = "f32le"
+stdin_sample_rate = 48000
+stdin_channels = 2
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    assert_eq!(config.audio.source, AudioSourceKind::Stdin);
+    assert_eq!(config.audio.stdin_format, PcmFormat::F32le);
+    assert_eq!(config.audio.stdin_sample_rate, 48000);
+    assert_eq!(config.audio.stdin_channels, 2);
+}
+
+#[test]
+fn test_audio_file_source_parsed() {
+    let toml = r#"
+[audio]
+source = "file"
+path = "/recordings/session.flac"
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    assert_eq!(config.audio.source, AudioSourceKind::File);
+    assert_eq!(
+        config.audio.path,
+        Some(PathBuf::from("/recordings/session.flac"))
+    );
+}
+
+#[test]
+fn test_vad_roll_durations_parsed() {
+    let toml = r#"
+[vad]
+pre_roll_ms = 0
+post_roll_ms = 400
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    assert_eq!(config.vad.pre_roll_ms, 0);
+    assert_eq!(config.vad.post_roll_ms, 400);
+}
+
+#[test]
+fn test_vad_tuning_parsed() {
+    let toml = r#"
+[vad]
+threshold = 0.6
+end_threshold = 0.45
+min_speech_ms = 100
+min_silence_ms = 700
+chunk_size = 1536
+"#;
+    let config = Config::parse(toml).unwrap();
+    assert!((config.vad.threshold - 0.6).abs() < f32::EPSILON);
+    assert!((config.vad.end_threshold - 0.45).abs() < f32::EPSILON);
+    assert_eq!(config.vad.min_speech_ms, 100);
+    assert_eq!(config.vad.min_silence_ms, Some(700));
+    assert_eq!(config.vad.chunk_size, 1536);
+}
+
+#[test]
+fn test_vad_invalid_chunk_size_rejected() {
+    let err = Config::parse("[vad]\nchunk_size = 1000\n").unwrap_err();
+    assert!(format!("{err:#}").contains("chunk_size"));
+}
+
+#[test]
+fn test_vad_threshold_out_of_range_rejected() {
+    assert!(Config::parse("[vad]\nthreshold = 1.5\n").is_err());
+}
+
+#[test]
+fn test_vad_end_threshold_above_threshold_rejected() {
+    let err = Config::parse("[vad]\nthreshold = 0.4\nend_threshold = 0.6\n").unwrap_err();
+    assert!(format!("{err:#}").contains("end_threshold"));
+}
+
+#[test]
+fn test_vad_detector_config_converts_ms_to_chunks() {
+    let config = VadConfig::default();
+    let detector = config.detector_config(LatencyMode::Balanced);
+    assert_eq!(detector.min_speech_chunks, 2);
+    assert_eq!(detector.min_silence_chunks, 8);
+
+    // Rounds up to whole chunks of 1024 samples (64 ms), at least one
+    let config = VadConfig {
+        chunk_size: 1024,
+        min_speech_ms: 0,
+        min_silence_ms: Some(100),
+        ..VadConfig::default()
+    };
+    let detector = config.detector_config(LatencyMode::Balanced);
+    assert_eq!(detector.min_speech_chunks, 1);
+    assert_eq!(detector.min_silence_chunks, 2);
+}
+
+#[test]
+fn test_vad_silence_timeout_follows_latency_mode_unless_set() {
+    let config = VadConfig::default();
+    let fast = config.detector_config(LatencyMode::Fast);
+    let accurate = config.detector_config(LatencyMode::Accurate);
+    assert!(fast.min_silence_chunks < accurate.min_silence_chunks);
+
+    let config = VadConfig {
+        min_silence_ms: Some(320),
+        ..VadConfig::default()
+    };
+    assert_eq!(
+        config.detector_config(LatencyMode::Fast).min_silence_chunks,
+        config
+            .detector_config(LatencyMode::Accurate)
+            .min_silence_chunks
+    );
+}
+
+#[test]
+fn test_vad_max_segment_overrides_latency_mode() {
+    let config = Config::parse("[vad]\nmax_segment_secs = 20.0\n").unwrap();
+    assert!((config.vad.max_segment_secs(LatencyMode::Fast) - 20.0).abs() < f32::EPSILON);
+
+    let config = VadConfig::default();
+    assert!(
+        (config.max_segment_secs(LatencyMode::Fast) - LatencyMode::Fast.profile().max_segment_secs)
+            .abs()
+            < f32::EPSILON
+    );
+}
+
+#[test]
+fn test_vad_max_segment_out_of_range_rejected() {
+    let err = Config::parse("[vad]\nmax_segment_secs = 31.0\n").unwrap_err();
+    assert!(format!("{err:#}").contains("max_segment_secs"));
+    assert!(Config::parse("[vad]\nmax_segment_secs = 0.5\n").is_err());
+}
+
+#[test]
+fn test_latency_profiles_trade_speed_for_accuracy() {
+    let fast = LatencyMode::Fast.profile();
+    let balanced = LatencyMode::Balanced.profile();
+    let accurate = LatencyMode::Accurate.profile();
+
+    assert!(fast.min_silence_ms < balanced.min_silence_ms);
+    assert!(balanced.min_silence_ms < accurate.min_silence_ms);
+    assert!(fast.max_segment_secs < accurate.max_segment_secs);
+    assert_eq!(balanced.beam_size, 1);
+    assert!(accurate.beam_size > 1);
+}
+
+#[test]
+fn test_latency_min_chunk_seconds_out_of_range_rejected() {
+    let err = Config::parse("[latency]\nmin_chunk_seconds = -1.0\n").unwrap_err();
+    assert!(format!("{err:#}").contains("min_chunk_seconds"));
+    assert!(Config::parse("[latency]\nmin_chunk_seconds = 45.0\n").is_err());
+}
+
+#[test]
+fn test_task_defaults_to_transcribe() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(config.model.task, Task::Transcribe);
+
+    let config = Config::parse("[model]\ntask = \"translate\"\n").unwrap();
+    assert_eq!(config.model.task, Task::Translate);
+}
+
+#[test]
+fn test_translate_with_english_only_model_rejected() {
+    let toml = r#"
+[model]
+model = "whisper-base-en"
+task = "translate"
+"#;
+    let err = Config::parse(toml).unwrap_err();
+    assert!(format!("{err:#}").contains("multilingual"));
+}
+
+#[test]
+fn test_whisper_parsed() {
+    let toml = r#"
+[whisper]
+beam_size = 4
+temperature = 0.2
+temperature_increment = 0.0
+threads = 6
+no_speech_threshold = 0.8
+entropy_threshold = 2.0
+logprob_threshold = -0.5
+suppress_blank = false
+suppress_non_speech = false
+"#;
+    let config = Config::parse(toml).unwrap();
+    let decoding = config.whisper.decoding(config.latency.mode);
+    assert_eq!(decoding.beam_size, 4);
+    assert!((decoding.temperature - 0.2).abs() < f32::EPSILON);
+    assert_eq!(decoding.temperature_increment, 0.0);
+    assert_eq!(decoding.threads, Some(6));
+    assert!((decoding.no_speech_threshold - 0.8).abs() < f32::EPSILON);
+    assert!((decoding.entropy_threshold - 2.0).abs() < f32::EPSILON);
+    assert!((decoding.logprob_threshold + 0.5).abs() < f32::EPSILON);
+    assert!(!decoding.suppress_blank);
+    assert!(!decoding.suppress_non_speech);
+    assert!(decoding.single_segment);
+}
+
+#[test]
+fn test_whisper_beam_size_defaults_to_latency_mode() {
+    let whisper = WhisperConfig::default();
+    assert_eq!(
+        whisper.decoding(LatencyMode::Accurate).beam_size,
+        LatencyMode::Accurate.profile().beam_size
+    );
+    assert_eq!(whisper.decoding(LatencyMode::Fast).beam_size, 1);
+}
+
+#[test]
+fn test_whisper_out_of_range_rejected() {
+    for (toml, field) in [
+        ("beam_size = 0", "beam_size"),
+        ("beam_size = 9", "beam_size"),
+        ("best_of = 0", "best_of"),
+        ("temperature = 1.5", "temperature"),
+        ("temperature_increment = -0.1", "temperature_increment"),
+        ("threads = 0", "threads"),
+        ("no_speech_threshold = 2.0", "no_speech_threshold"),
+        ("entropy_threshold = 0.0", "entropy_threshold"),
+        ("logprob_threshold = 0.5", "logprob_threshold"),
+    ] {
+        let err = Config::parse(&format!("[whisper]\n{toml}\n")).unwrap_err();
+        assert!(format!("{err:#}").contains(field), "{toml}: {err:#}");
+    }
+}
+
+#[test]
+fn test_filter_parsed() {
+    let toml = r#"
+[filter]
+blocklist = ["Bye."]
+max_repeats = 0
+max_chars_per_sec = 40.0
+"#;
+    let config = Config::parse(toml).unwrap();
+    assert_eq!(config.filter.blocklist, vec!["Bye.".to_string()]);
+    assert_eq!(config.filter.max_repeats, 0);
+    assert!((config.filter.max_chars_per_sec - 40.0).abs() < f32::EPSILON);
+}
+
+#[test]
+fn test_filter_negative_char_rate_rejected() {
+    let err = Config::parse("[filter]\nmax_chars_per_sec = -1.0\n").unwrap_err();
+    assert!(format!("{err:#}").contains("max_chars_per_sec"));
+}
+
+#[test]
+fn test_postprocess_parsed() {
+    let toml = r#"
+[postprocess]
+stages = ["filter_words", "punctuation_commands", "whitespace", "capitalization"]
+filter_words = ["um", "you know"]
+
+[postprocess.replacements]
+"gee mail" = "Gmail"
+"#;
+    let config = Config::parse(toml).unwrap();
+    assert_eq!(
+        config.postprocess.stages,
+        vec![
+            PostprocessStage::FilterWords,
+            PostprocessStage::PunctuationCommands,
+            PostprocessStage::Whitespace,
+            PostprocessStage::Capitalization,
+        ]
+    );
+    assert_eq!(config.postprocess.filter_words, vec!["um", "you know"]);
+    assert_eq!(config.postprocess.replacements["gee mail"], "Gmail");
+}
+
+#[test]
+fn test_postprocess_blank_phrases_rejected() {
+    let err = Config::parse("[postprocess]\nfilter_words = [\" \"]\n").unwrap_err();
+    assert!(format!("{err:#}").contains("filter_words"));
+    let err = Config::parse("[postprocess.replacements]\n\"\" = \"x\"\n").unwrap_err();
+    assert!(format!("{err:#}").contains("replacements"));
+}
+
+#[test]
+fn test_spoken_punctuation_needs_commands_stage() {
+    let toml = r#"
+[postprocess]
+punctuation = "spoken"
+stages = ["whitespace"]
+"#;
+    let err = Config::parse(toml).unwrap_err();
+    assert!(format!("{err:#}").contains("punctuation_commands"));
+
+    let toml = r#"
+[postprocess]
+punctuation = "spoken"
+stages = ["punctuation_commands", "capitalization"]
+"#;
+    let config = Config::parse(toml).unwrap();
+    assert_eq!(config.postprocess.punctuation, PunctuationMode::Spoken);
+}
+
+#[test]
+fn test_http_backend_parsed() {
+    let toml = r#"
+[model]
+backend = "http"
+
+[http]
+url = "http://192.168.1.10:8000"
+model = "Systran/faster-whisper-small"
+timeout_ms = 3000
+fallback = false
+"#;
+    let config = Config::parse(toml).unwrap();
+    assert_eq!(config.model.backend, Backend::Http);
+    assert_eq!(config.http.url, "http://192.168.1.10:8000");
+    assert_eq!(config.http.model, "Systran/faster-whisper-small");
+    assert_eq!(config.http.api_key, None);
+    assert_eq!(config.http.timeout_ms, 3000);
+    assert!(!config.http.fallback);
+}
+
+#[test]
+fn test_http_backend_defaults() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(config.model.backend, Backend::Whisper);
+    assert_eq!(config.http.url, "http://127.0.0.1:8000");
+    assert!(config.http.fallback);
+}
+
+#[test]
+fn test_http_invalid_url_rejected() {
+    let err = Config::parse("[http]\nurl = \"localhost:8000\"\n").unwrap_err();
+    assert!(format!("{err:#}").contains("http.url"));
+    let err = Config::parse("[http]\ntimeout_ms = 0\n").unwrap_err();
+    assert!(format!("{err:#}").contains("http.timeout_ms"));
+}
+
+#[test]
+fn test_streaming_parsed() {
+    let toml = r#"
+[streaming]
+enabled = true
+interval_ms = 750
+inject_stable_prefix = true
+"#;
+    let config = Config::parse(toml).unwrap();
+    assert!(config.streaming.enabled);
+    assert_eq!(config.streaming.interval_ms, 750);
+    assert!(config.streaming.inject_stable_prefix);
+}
+
+#[test]
+fn test_streaming_interval_too_short_rejected() {
+    let err = Config::parse("[streaming]\ninterval_ms = 10\n").unwrap_err();
+    assert!(format!("{err:#}").contains("interval_ms"));
+}
+
+#[test]
+fn test_audio_device_defaults_to_system_default() {
+    let config: Config = toml::from_str("").unwrap();
+    assert!(config.audio.device.is_none());
+    assert_eq!(config.audio.device_fallback, DeviceFallback::Default);
+}
+
+#[test]
+fn test_audio_device_and_fallback_parsed() {
+    let toml = r#"
+[audio]
+device = "Jabra Evolve2 65"
+device_fallback = "fail"
+"#;
+    let config: Config = toml::from_str(toml).unwrap();
+    assert_eq!(config.audio.device.as_deref(), Some("Jabra Evolve2 65"));
+    assert_eq!(config.audio.device_fallback, DeviceFallback::Fail);
+}
+
+#[test]
+fn test_speech_model_parsed_from_name() {
+    assert_eq!(
+        "whisper-large-v3-turbo".parse::<SpeechModel>().unwrap(),
+        SpeechModel::WhisperLargeV3Turbo
+    );
+    assert_eq!(
+        "whisper-base-en".parse::<SpeechModel>().unwrap(),
+        SpeechModel::WhisperBaseEn
+    );
+    let err = "whisper-huge".parse::<SpeechModel>().unwrap_err();
+    assert!(err.to_string().contains("whisper-huge"));
+}
+
+#[test]
+fn test_process_backend_parsed() {
+    let toml = r#"
+[model]
+backend = "process"
+
+[process]
+command = "/opt/canary/serve"
+args = ["--quiet"]
+timeout_ms = 2000
+"#;
+    let config = Config::parse(toml).unwrap();
+    assert_eq!(config.model.backend, Backend::Process);
+    assert_eq!(config.process.command, "/opt/canary/serve");
+    assert_eq!(config.process.args, vec!["--quiet"]);
+    assert_eq!(config.process.timeout_ms, 2000);
+}
+
+#[test]
+fn test_process_backend_needs_command() {
+    let err = Config::parse("[model]\nbackend = \"process\"\n").unwrap_err();
+    assert!(format!("{err:#}").contains("process.command"));
+    let err = Config::parse("[process]\ntimeout_ms = 0\n").unwrap_err();
+    assert!(format!("{err:#}").contains("process.timeout_ms"));
+}
@@ -1,15 +1,30 @@
 //! Controller manages daemon state and coordinates components.
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tokio::sync::{Mutex, RwLock, broadcast, oneshot};
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
-use vcm_proto::{Event, State, StateChange, Transcription};
-
-use crate::config::{Config, InitialState, InjectionConfig};
-use crate::engine::{Engine, SharedLanguage};
-use crate::inject::KeystrokeInjector;
+use tracing::{error, info, warn};
+use vcm_proto::{
+    Event, ModelDownload, ModelLoad, ModelSwitch, Ready, State, StateChange, TranscriptSegment,
+    Transcription,
+};
+
+use crate::audio::InputDeviceInfo;
+use crate::config::{Config, DeviceFallback, InitialState, InjectionConfig, SpeechModel, Task};
+use crate::daemon::init_event_to_proto;
+use crate::engine::{
+    Engine, EngineEvent, InitEvent, LiveSettings, SharedLanguage, SharedSettings,
+    SharedTranscriber, load_transcriber, speech_model_to_model_id,
+};
+use crate::inject::{InjectorFactory, keystroke_injector_factory};
+use crate::models::ModelManager;
+use crate::postprocess::is_undo_command;
+use crate::streaming::PrefixTracker;
+use crate::transcribe::{FilterCounts, Transcript};
+use crate::typist::{SharedTypingState, Typist};
 
 /// Controller state.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -31,6 +46,32 @@ impl From<ControllerState> for State {
     }
 }
 
+/// Input devices as seen by the controller, for `ListAudioDevices`.
+#[derive(Debug, Clone)]
+pub struct AudioDeviceList {
+    pub devices: Vec<InputDeviceInfo>,
+    /// Index of the device capture would use, if any.
+    pub selected: Option<usize>,
+    /// Device selector from `[audio] device`.
+    pub configured: Option<String>,
+    /// Whether a device is configured but not currently connected.
+    pub configured_missing: bool,
+}
+
+/// Language auto-detected for the most recent transcription.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedLanguage {
+    /// ISO 639-1 code.
+    pub code: String,
+    pub probability: f32,
+}
+
+/// Shared slot for the last detected language, written by the engine task.
+type SharedDetectedLanguage = Arc<std::sync::Mutex<Option<DetectedLanguage>>>;
+
+/// Hallucination filter counts since the daemon started, written by the engine task.
+type SharedFilterCounts = Arc<std::sync::Mutex<FilterCounts>>;
+
 /// Event sender type.
 pub type EventSender = broadcast::Sender<Event>;
 
@@ -48,9 +89,24 @@ pub struct Controller {
     engine: Arc<Mutex<Option<Engine>>>,
     engine_handle: Arc<RwLock<Option<EngineHandle>>>,
     injection_config: InjectionConfig,
+    /// Creates the injector for each listening session.
+    injector_factory: InjectorFactory,
+    /// What was typed recently, kept across sessions for joining and undo.
+    typing_state: SharedTypingState,
     initial_state: InitialState,
     shared_language: SharedLanguage,
+    shared_settings: SharedSettings,
     config: Arc<RwLock<Config>>,
+    detected_language: SharedDetectedLanguage,
+    filter_counts: SharedFilterCounts,
+    pending_transcriber: SharedTranscriber,
+    model_manager: ModelManager,
+    /// A model is being downloaded or loaded for `set_model`.
+    switching_model: Arc<AtomicBool>,
+    /// The models were unloaded after being paused for `[daemon] idle_unload_secs`.
+    models_unloaded: Arc<AtomicBool>,
+    /// Cancels the pending idle unload.
+    idle_timer: std::sync::Mutex<Option<CancellationToken>>,
 }
 
 impl Controller {
@@ -62,6 +118,9 @@ impl Controller {
         config: Config,
     ) -> Self {
         let shared_language = engine.shared_language();
+        let shared_settings = engine.shared_settings();
+        let pending_transcriber = engine.pending_transcriber();
+        let model_manager = engine.model_manager().clone();
         let injection_config = config.injection.clone();
         let initial_state = config.daemon.initial_state;
         Self {
@@ -71,12 +130,29 @@ impl Controller {
             engine: Arc::new(Mutex::new(Some(engine))),
             engine_handle: Arc::new(RwLock::new(None)),
             injection_config,
+            injector_factory: keystroke_injector_factory(),
+            typing_state: SharedTypingState::default(),
             initial_state,
             shared_language,
+            shared_settings,
             config: Arc::new(RwLock::new(config)),
+            detected_language: Arc::new(std::sync::Mutex::new(None)),
+            filter_counts: Arc::new(std::sync::Mutex::new(FilterCounts::default())),
+            pending_transcriber,
+            model_manager,
+            switching_model: Arc::new(AtomicBool::new(false)),
+            models_unloaded: Arc::new(AtomicBool::new(false)),
+            idle_timer: std::sync::Mutex::new(None),
         }
     }
 
+    /// Deliver transcriptions through injectors from `factory` instead of
+    /// typing them as keystrokes.
+    pub fn with_injector(mut self, factory: InjectorFactory) -> Self {
+        self.injector_factory = factory;
+        self
+    }
+
     /// Get the current state.
     pub async fn state(&self) -> ControllerState {
         *self.state.read().await
@@ -93,11 +169,15 @@ impl Controller {
             self.broadcast_state_change(ControllerState::Paused);
         }
 
-        if self.initial_state == InitialState::Listening
-            && let Err(e) = self.start_listening().await
-        {
-            error!(error = %e, "Failed to auto-start listening after initialization");
+        if self.initial_state == InitialState::Listening {
+            match self.start_listening().await {
+                Ok(()) => return,
+                Err(e) => {
+                    error!(error = %e, "Failed to auto-start listening after initialization");
+                }
+            }
         }
+        self.schedule_idle_unload().await;
     }
 
     /// Start listening — spawns the engine audio loop.
@@ -105,8 +185,10 @@ impl Controller {
         let mut state = self.state.write().await;
         match *state {
             ControllerState::Paused => {
+                self.cancel_idle_unload();
+
                 // Take engine out
-                let engine = self
+                let mut engine = self
                     .engine
                     .lock()
                     .await
@@ -114,18 +196,58 @@ impl Controller {
                     .ok_or("Engine not available")?;
 
                 if !engine.is_initialized() {
-                    // Put it back
-                    *self.engine.lock().await = Some(engine);
-                    return Err("Engine not initialized".to_string());
+                    if !self.models_unloaded.load(Ordering::SeqCst) {
+                        // Put it back
+                        *self.engine.lock().await = Some(engine);
+                        return Err("Engine not initialized".to_string());
+                    }
+
+                    // Loading takes seconds; other requests see the daemon
+                    // initializing rather than waiting for the lock
+                    *state = ControllerState::Initializing;
+                    self.broadcast_state_change(ControllerState::Initializing);
+                    drop(state);
+                    let result = self.reload_engine(&mut engine).await;
+                    state = self.state.write().await;
+
+                    let result = match *state {
+                        ControllerState::Initializing => result,
+                        // Shut down while loading
+                        _ => Err("Daemon is stopped".to_string()),
+                    };
+                    if let Err(e) = result {
+                        *self.engine.lock().await = Some(engine);
+                        if *state == ControllerState::Initializing {
+                            *state = ControllerState::Paused;
+                            self.broadcast_state_change(ControllerState::Paused);
+                        }
+                        return Err(e);
+                    }
                 }
 
                 let cancel = CancellationToken::new();
                 let cancel_clone = cancel.clone();
                 let event_tx = self.event_tx.clone();
                 let injection_config = self.injection_config.clone();
+                let context_reset = injection_config.context_reset();
+                let injector_factory = Arc::clone(&self.injector_factory);
+                let typing_state = Arc::clone(&self.typing_state);
+                let shared_settings = Arc::clone(&self.shared_settings);
+                let detected_language = Arc::clone(&self.detected_language);
+                let filter_counts = Arc::clone(&self.filter_counts);
 
                 let join = tokio::spawn(async move {
-                    run_engine_task(engine, cancel_clone, event_tx, injection_config).await
+                    run_engine_task(
+                        engine,
+                        cancel_clone,
+                        event_tx,
+                        injector_factory(injection_config)
+                            .map(|injector| Typist::new(injector, context_reset, typing_state)),
+                        shared_settings,
+                        detected_language,
+                        filter_counts,
+                    )
+                    .await
                 });
 
                 *self.engine_handle.write().await = Some(EngineHandle { cancel, join });
@@ -148,10 +270,12 @@ impl Controller {
                 if let Some(handle) = self.engine_handle.write().await.take() {
                     handle.cancel.cancel();
                     match handle.join.await {
-                        Ok((engine, result)) => {
+                        Ok((mut engine, result)) => {
                             if let Err(e) = result {
                                 error!(error = %e, "Engine task finished with error");
                             }
+                            // A model loaded just as the loop stopped
+                            engine.apply_pending_transcriber();
                             *self.engine.lock().await = Some(engine);
                         }
                         Err(e) => {
@@ -163,6 +287,8 @@ impl Controller {
 
                 *state = ControllerState::Paused;
                 self.broadcast_state_change(ControllerState::Paused);
+                drop(state);
+                self.schedule_idle_unload().await;
                 Ok(())
             }
             ControllerState::Paused => Ok(()),
@@ -175,6 +301,7 @@ impl Controller {
     pub async fn shutdown(&self) {
         // Stop listening first if active
         let _ = self.stop_listening().await;
+        self.cancel_idle_unload();
 
         let mut state = self.state.write().await;
         *state = ControllerState::Stopped;
@@ -185,6 +312,71 @@ impl Controller {
         }
     }
 
+    /// Unload the models once the daemon has been paused for
+    /// `[daemon] idle_unload_secs`, unless listening starts first.
+    async fn schedule_idle_unload(&self) {
+        let secs = self.config.read().await.daemon.idle_unload_secs;
+        self.cancel_idle_unload();
+        if secs == 0 {
+            return;
+        }
+
+        let cancel = CancellationToken::new();
+        if let Ok(mut timer) = self.idle_timer.lock() {
+            *timer = Some(cancel.clone());
+        }
+        let state = Arc::clone(&self.state);
+        let engine = Arc::clone(&self.engine);
+        let models_unloaded = Arc::clone(&self.models_unloaded);
+        tokio::spawn(async move {
+            tokio::select! {
+                () = cancel.cancelled() => return,
+                () = tokio::time::sleep(Duration::from_secs(secs)) => {}
+            }
+            // Holding the state keeps listening from starting meanwhile
+            let state = state.read().await;
+            if *state != ControllerState::Paused || cancel.is_cancelled() {
+                return;
+            }
+            if let Some(engine) = engine.lock().await.as_mut()
+                && engine.is_initialized()
+            {
+                engine.unload();
+                models_unloaded.store(true, Ordering::SeqCst);
+                info!(idle_secs = secs, "Unloaded models after idle timeout");
+            }
+        });
+    }
+
+    /// Cancel the pending idle unload, if any.
+    fn cancel_idle_unload(&self) {
+        if let Some(cancel) = self.idle_timer.lock().ok().and_then(|mut t| t.take()) {
+            cancel.cancel();
+        }
+    }
+
+    /// Load the models again after an idle unload, broadcasting the same
+    /// progress events as initialization.
+    async fn reload_engine(&self, engine: &mut Engine) -> Result<(), String> {
+        info!("Reloading models after idle unload");
+        engine.set_model(self.config.read().await.model.model);
+        let tx = self.event_tx.clone();
+        engine
+            .initialize(move |event| {
+                let _ = tx.send(init_event_to_proto(event));
+            })
+            .await
+            .map_err(|e| format!("Failed to reload models: {e:#}"))?;
+        self.models_unloaded.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Whether the models were unloaded while idle; they load again when
+    /// listening starts.
+    pub fn models_unloaded(&self) -> bool {
+        self.models_unloaded.load(Ordering::SeqCst)
+    }
+
     /// Get the engine for initialization (used by daemon runner).
     pub async fn take_engine(&self) -> Option<Engine> {
         self.engine.lock().await.take()
@@ -197,14 +389,7 @@ impl Controller {
 
     /// Broadcast a state change event.
     fn broadcast_state_change(&self, new_state: ControllerState) {
-        let event = Event {
-            event: Some(vcm_proto::event::Event::StateChange(StateChange {
-                status: Some(vcm_proto::state_change::Status::NewState(
-                    State::from(new_state).into(),
-                )),
-            })),
-        };
-        let _ = self.event_tx.send(event);
+        let _ = self.event_tx.send(state_change_event(new_state));
     }
 
     /// Broadcast an error event.
@@ -228,13 +413,16 @@ impl Controller {
 
     /// Set the transcription language at runtime.
     ///
-    /// Pass `"auto"` for automatic detection, or a language code like `"en"`, `"cs"`, etc.
-    /// The change takes effect on the next transcription call and is persisted to the config file.
+    /// Pass `"auto"` for automatic detection, or a language code or name like `"en"`,
+    /// `"cs"` or `"slovak"`. The change takes effect on the next transcription call and is
+    /// persisted to the config file.
     pub async fn set_language(&self, language: &str) -> Result<(), String> {
         let lang = if language == "auto" {
             None
         } else {
-            Some(language.to_string())
+            let code = crate::transcribe::language_code(language)
+                .ok_or_else(|| format!("Unknown language {language:?}"))?;
+            Some(code.to_string())
         };
 
         // Persist to config first so failures don't partially apply the change
@@ -259,6 +447,184 @@ impl Controller {
         Ok(())
     }
 
+    /// Switch between transcribing and translating to English.
+    ///
+    /// The change takes effect on the next transcription and is persisted to the config file.
+    pub async fn set_task(&self, task: Task) -> Result<(), String> {
+        {
+            let mut config = self.config.write().await;
+            let mut updated = config.clone();
+            updated.model.task = task;
+            updated.validate().map_err(|e| format!("{e:#}"))?;
+            updated
+                .save()
+                .map_err(|e| format!("Failed to save config: {e}"))?;
+            *config = updated;
+        }
+
+        self.shared_settings
+            .lock()
+            .map_err(|e| format!("Failed to lock live settings: {e}"))?
+            .task = task;
+        info!(task = ?task, "Task changed");
+        Ok(())
+    }
+
+    /// Whether transcriptions are typed as spoken or translated to English.
+    pub async fn task(&self) -> Task {
+        self.config.read().await.model.task
+    }
+
+    /// Switch to a different speech model without restarting.
+    ///
+    /// Returns once the switch has started. The model is downloaded if needed
+    /// and loaded in the background while the current one keeps transcribing;
+    /// speech already queued finishes on the old model. Progress is broadcast
+    /// as `ModelSwitch` events, and the new model is persisted to the config
+    /// file once it is loaded.
+    pub async fn set_model(&self, model: SpeechModel) -> Result<(), String> {
+        match self.state().await {
+            ControllerState::Initializing => {
+                return Err("Daemon is still initializing".to_string());
+            }
+            ControllerState::Stopped => return Err("Daemon is stopped".to_string()),
+            ControllerState::Listening | ControllerState::Paused => {}
+        }
+
+        let model_name = speech_model_to_model_id(model).to_string();
+        let (updated, language) = {
+            let config = self.config.read().await;
+            if config.model.model == model {
+                let ready = model_switch_progress(InitEvent::Ready);
+                let _ = self.event_tx.send(model_switch_event(&model_name, ready));
+                return Ok(());
+            }
+            let mut updated = config.clone();
+            updated.model.model = model;
+            updated.validate().map_err(|e| format!("{e:#}"))?;
+            let language = self
+                .shared_language
+                .lock()
+                .map_err(|e| format!("Failed to lock shared language: {e}"))?
+                .clone();
+            (updated, language)
+        };
+
+        if self.switching_model.swap(true, Ordering::SeqCst) {
+            return Err("A model switch is already in progress".to_string());
+        }
+        info!(model = %model_name, "Switching model");
+
+        let config = Arc::clone(&self.config);
+        let engine = Arc::clone(&self.engine);
+        let pending = Arc::clone(&self.pending_transcriber);
+        let model_manager = self.model_manager.clone();
+        let switching = Arc::clone(&self.switching_model);
+        let event_tx = self.event_tx.clone();
+        tokio::spawn(async move {
+            let tx = event_tx.clone();
+            let name = model_name.clone();
+            let on_progress = move |event| {
+                let _ = tx.send(model_switch_event(&name, model_switch_progress(event)));
+            };
+            let result =
+                match load_transcriber(&model_manager, &updated, language, &on_progress).await {
+                    Ok(transcriber) => persist_model(&config, model).await.map(|()| transcriber),
+                    Err(e) => Err(format!("{e:#}")),
+                };
+
+            let progress = match result {
+                Ok(transcriber) => {
+                    if let Ok(mut slot) = pending.lock() {
+                        *slot = Some(transcriber);
+                    }
+                    // A running loop picks the transcriber up itself
+                    if let Some(engine) = engine.lock().await.as_mut() {
+                        engine.apply_pending_transcriber();
+                    }
+                    info!(model = %model_name, "Model switched");
+                    model_switch_progress(InitEvent::Ready)
+                }
+                Err(message) => {
+                    error!(model = %model_name, error = %message, "Model switch failed");
+                    vcm_proto::model_switch::Progress::Error(vcm_proto::Error { message })
+                }
+            };
+            switching.store(false, Ordering::SeqCst);
+            let _ = event_tx.send(model_switch_event(&model_name, progress));
+        });
+
+        Ok(())
+    }
+
+    /// Speech model in use.
+    pub async fn model(&self) -> SpeechModel {
+        self.config.read().await.model.model
+    }
+
+    /// Add vocabulary terms for all languages, or only for `language`.
+    ///
+    /// Saved to the config file and used from the next transcription on.
+    /// Returns how many terms were new.
+    pub async fn add_vocabulary_terms(
+        &self,
+        terms: &[String],
+        language: Option<&str>,
+    ) -> Result<usize, String> {
+        let (vocabulary, added) = {
+            let mut config = self.config.write().await;
+            let mut updated = config.clone();
+            let added = updated
+                .vocabulary
+                .add_terms(terms, language)
+                .map_err(|e| format!("{e:#}"))?;
+            if added == 0 {
+                return Ok(0);
+            }
+            updated
+                .save()
+                .map_err(|e| format!("Failed to save config: {e}"))?;
+            *config = updated;
+            (config.vocabulary.clone(), added)
+        };
+
+        self.shared_settings
+            .lock()
+            .map_err(|e| format!("Failed to lock live settings: {e}"))?
+            .vocabulary = vocabulary;
+        info!(added = added, language = ?language, "Vocabulary terms added");
+        Ok(added)
+    }
+
+    /// Delete the most recently dictated utterance from the frontmost app.
+    ///
+    /// Refuses when another app has come to the front since it was typed.
+    /// Returns how many characters were deleted.
+    pub fn undo_last(&self) -> Result<usize, String> {
+        let injector = (self.injector_factory)(self.injection_config.clone())
+            .map_err(|e| format!("Failed to create injector: {e:#}"))?;
+        Typist::new(
+            injector,
+            self.injection_config.context_reset(),
+            Arc::clone(&self.typing_state),
+        )
+        .undo_last()
+        .map_err(|e| format!("{e:#}"))
+    }
+
+    /// Language auto-detected for the most recent transcription, if any.
+    pub fn detected_language(&self) -> Option<DetectedLanguage> {
+        self.detected_language.lock().ok()?.clone()
+    }
+
+    /// How often the hallucination filter removed text since the daemon started.
+    pub fn filter_counts(&self) -> FilterCounts {
+        self.filter_counts
+            .lock()
+            .map(|counts| *counts)
+            .unwrap_or_default()
+    }
+
     /// Get the current language and the list of available languages from config.
     ///
     /// Returns `(active_language, available_languages)`.
@@ -273,6 +639,166 @@ impl Controller {
         let available = self.config.read().await.gui.languages.clone();
         (active, available)
     }
+
+    /// Re-read the config file and apply the settings that can change at runtime.
+    ///
+    /// `[model] language`, `candidate_languages` and `task`, `[gui] languages`, `[vad]`,
+    /// `[latency]`, `[whisper]`, `[filter]`, `[streaming]` and `[vocabulary]` take effect
+    /// immediately, even while listening. Other sections are only read at startup.
+    pub async fn reload_config(&self) -> Result<(), String> {
+        let config = Config::load().map_err(|e| format!("{e:#}"))?;
+        self.apply_config(config).await
+    }
+
+    /// Apply runtime-changeable settings from `config` and keep it as the current config.
+    async fn apply_config(&self, config: Config) -> Result<(), String> {
+        config.validate().map_err(|e| format!("{e:#}"))?;
+
+        let language = config.model.language_code();
+        *self
+            .shared_language
+            .lock()
+            .map_err(|e| format!("Failed to lock shared language: {e}"))? = language;
+        *self
+            .shared_settings
+            .lock()
+            .map_err(|e| format!("Failed to lock live settings: {e}"))? =
+            LiveSettings::from_config(&config);
+
+        *self.config.write().await = config;
+        info!("Configuration reloaded");
+        Ok(())
+    }
+
+    /// List input devices along with which one capture would use right now.
+    pub async fn list_audio_devices(&self) -> Result<AudioDeviceList, String> {
+        let devices = tokio::task::spawn_blocking(crate::audio::list_input_devices)
+            .await
+            .map_err(|e| format!("Device enumeration task failed: {e}"))?
+            .map_err(|e| format!("{e:#}"))?;
+
+        let (configured, fallback) = {
+            let config = self.config.read().await;
+            (config.audio.device.clone(), config.audio.device_fallback)
+        };
+        let matched = configured
+            .as_deref()
+            .and_then(|selector| crate::audio::match_device(&devices, selector));
+        let configured_missing = configured.is_some() && matched.is_none();
+        let selected = match matched {
+            Some(index) => Some(index),
+            None if configured_missing && fallback == DeviceFallback::Fail => None,
+            None => devices.iter().position(|d| d.is_default),
+        };
+
+        Ok(AudioDeviceList {
+            devices,
+            selected,
+            configured,
+            configured_missing,
+        })
+    }
+}
+
+/// Build a state change event for broadcasting.
+fn state_change_event(new_state: ControllerState) -> Event {
+    Event {
+        event: Some(vcm_proto::event::Event::StateChange(StateChange {
+            status: Some(vcm_proto::state_change::Status::NewState(
+                State::from(new_state).into(),
+            )),
+        })),
+    }
+}
+
+/// Save `model` as `[model] model`, checking it still fits the current config.
+async fn persist_model(config: &RwLock<Config>, model: SpeechModel) -> Result<(), String> {
+    let mut config = config.write().await;
+    let mut updated = config.clone();
+    updated.model.model = model;
+    updated.validate().map_err(|e| format!("{e:#}"))?;
+    updated
+        .save()
+        .map_err(|e| format!("Failed to save config: {e}"))?;
+    *config = updated;
+    Ok(())
+}
+
+/// Build a model switch progress event for broadcast.
+fn model_switch_event(model_name: &str, progress: vcm_proto::model_switch::Progress) -> Event {
+    Event {
+        event: Some(vcm_proto::event::Event::ModelSwitch(ModelSwitch {
+            model_name: model_name.to_string(),
+            progress: Some(progress),
+        })),
+    }
+}
+
+/// Report model loading progress as model switch progress.
+fn model_switch_progress(event: InitEvent) -> vcm_proto::model_switch::Progress {
+    use vcm_proto::model_switch::Progress;
+    match event {
+        InitEvent::Downloading {
+            model,
+            bytes,
+            total,
+        } => Progress::ModelDownload(ModelDownload {
+            model_name: model,
+            bytes_downloaded: bytes,
+            bytes_total: total,
+        }),
+        InitEvent::Loading { model } => Progress::ModelLoad(ModelLoad { model_name: model }),
+        InitEvent::Ready => Progress::Ready(Ready {}),
+    }
+}
+
+/// Build a transcription event for broadcast.
+fn transcription_event(transcript: Transcript, is_partial: bool) -> Event {
+    Event {
+        event: Some(vcm_proto::event::Event::Transcription(Transcription {
+            confidence: transcript.confidence().into(),
+            avg_logprob: transcript.avg_logprob.into(),
+            no_speech_prob: transcript.no_speech_prob.into(),
+            language: transcript.language.unwrap_or_default(),
+            language_probability: transcript.language_probability.unwrap_or_default().into(),
+            segments: transcript
+                .segments
+                .into_iter()
+                .map(|segment| TranscriptSegment {
+                    start_ms: segment.start_ms,
+                    end_ms: segment.end_ms,
+                    text: segment.text,
+                })
+                .collect(),
+            raw_text: transcript
+                .raw_text
+                .unwrap_or_else(|| transcript.text.clone()),
+            text: transcript.text,
+            is_partial,
+        })),
+    }
+}
+
+/// Delete the utterance before a spoken undo command.
+fn undo_by_voice(typist: &mut Typist) {
+    // Words of the command itself may already be typed as a stable prefix
+    match typist.finish_utterance() {
+        Ok(true) => {
+            if let Err(e) = typist.undo_last() {
+                warn!(error = %e, "Failed to delete the undo command");
+                return;
+            }
+        }
+        Ok(false) => {}
+        Err(e) => {
+            error!(error = %e, "Failed to record typed utterance");
+            return;
+        }
+    }
+    match typist.undo_last() {
+        Ok(chars) => info!(chars = chars, "Undo command -> deleted last utterance"),
+        Err(e) => warn!(error = %e, "Undo command failed"),
+    }
 }
 
 /// Run the engine in a background task, returning the engine when done.
@@ -280,26 +806,92 @@ async fn run_engine_task(
     mut engine: Engine,
     cancel: CancellationToken,
     event_tx: EventSender,
-    injection_config: InjectionConfig,
+    typist: anyhow::Result<Typist>,
+    shared_settings: SharedSettings,
+    detected_language: SharedDetectedLanguage,
+    filter_counts: SharedFilterCounts,
 ) -> (Engine, anyhow::Result<()>) {
-    let result = match KeystrokeInjector::new(injection_config) {
-        Ok(mut injector) => {
+    let result = match typist {
+        Ok(mut typist) => {
             let tx = event_tx.clone();
+            let mut tracker = PrefixTracker::new();
             engine
-                .run_loop(cancel, move |text| {
-                    info!(text = %text, "Transcription -> injecting");
-                    if let Err(e) = injector.inject_text(text) {
-                        error!(error = %e, "Keystroke injection failed");
+                .run_loop(cancel, move |event| match event {
+                    EngineEvent::Transcription(transcript) => {
+                        if let (Some(code), Some(probability)) =
+                            (&transcript.language, transcript.language_probability)
+                            && let Ok(mut detected) = detected_language.lock()
+                        {
+                            *detected = Some(DetectedLanguage {
+                                code: code.clone(),
+                                probability,
+                            });
+                        }
+                        let remaining = tracker.on_final(&transcript.text);
+                        if is_undo_command(&transcript.text, transcript.text_language()) {
+                            undo_by_voice(&mut typist);
+                        } else if !remaining.is_empty() {
+                            info!(text = %remaining, "Transcription -> injecting");
+                            if let Err(e) = typist.type_text(&remaining) {
+                                error!(error = %e, "Keystroke injection failed");
+                            }
+                        }
+                        if let Err(e) = typist.finish_utterance() {
+                            error!(error = %e, "Failed to record typed utterance");
+                        }
+                        let _ = tx.send(transcription_event(transcript, false));
+                    }
+                    EngineEvent::Partial(transcript) => {
+                        let inject_stable_prefix = shared_settings
+                            .lock()
+                            .is_ok_and(|settings| settings.streaming.inject_stable_prefix);
+                        if inject_stable_prefix
+                            && let Some(stable) = tracker.on_partial(&transcript.text)
+                        {
+                            info!(text = %stable, "Stable prefix -> injecting");
+                            if let Err(e) = typist.type_text(&stable) {
+                                error!(error = %e, "Keystroke injection failed");
+                            }
+                        }
+                        let _ = tx.send(transcription_event(transcript, true));
+                    }
+                    EngineEvent::Filtered(reason) => {
+                        if let Ok(mut counts) = filter_counts.lock() {
+                            counts.record(reason);
+                        }
+                    }
+                    EngineEvent::AudioError(message) => {
+                        let event = Event {
+                            event: Some(vcm_proto::event::Event::DaemonError(
+                                vcm_proto::DaemonError {
+                                    kind: vcm_proto::ErrorKind::ErrorAudioDevice.into(),
+                                    message,
+                                    model_name: String::new(),
+                                },
+                            )),
+                        };
+                        let _ = tx.send(event);
+                    }
+                    EngineEvent::SegmentsDropped(dropped) => {
+                        let event = Event {
+                            event: Some(vcm_proto::event::Event::DaemonError(
+                                vcm_proto::DaemonError {
+                                    kind: vcm_proto::ErrorKind::ErrorTranscriptionBacklog.into(),
+                                    message: format!(
+                                        "Transcription fell behind; dropped {} speech segment(s), {:.0} s of audio",
+                                        dropped.count, dropped.audio_secs
+                                    ),
+                                    model_name: String::new(),
+                                },
+                            )),
+                        };
+                        let _ = tx.send(event);
+                    }
+                    EngineEvent::AudioRecovered => {
+                        info!("Audio capture recovered");
+                        // Clients showing the error go back to Listening
+                        let _ = tx.send(state_change_event(ControllerState::Listening));
                     }
-                    // Broadcast transcription event
-                    let event = Event {
-                        event: Some(vcm_proto::event::Event::Transcription(Transcription {
-                            text: text.to_string(),
-                            confidence: 0.0,
-                            is_partial: false,
-                        })),
-                    };
-                    let _ = tx.send(event);
                 })
                 .await
         }
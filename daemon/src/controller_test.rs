@@ -1,5 +1,5 @@
 use super::*;
-use crate::config::{Config, DaemonConfig, InitialState};
+use crate::config::{Config, DaemonConfig, InitialState, LatencyMode};
 
 fn create_controller() -> (Controller, oneshot::Receiver<()>) {
     create_controller_with_initial_state(InitialState::Paused)
@@ -11,7 +11,10 @@ fn create_controller_with_initial_state(
     let (event_tx, _) = broadcast::channel(16);
     let (shutdown_tx, shutdown_rx) = oneshot::channel();
     let config = Config {
-        daemon: DaemonConfig { initial_state },
+        daemon: DaemonConfig {
+            initial_state,
+            ..DaemonConfig::default()
+        },
         ..Config::default()
     };
     let engine = Engine::new(config.clone()).unwrap();
@@ -85,6 +88,7 @@ async fn test_mark_ready_broadcasts_event() {
     let config = Config {
         daemon: DaemonConfig {
             initial_state: InitialState::Paused,
+            ..DaemonConfig::default()
         },
         ..Config::default()
     };
@@ -97,10 +101,199 @@ async fn test_mark_ready_broadcasts_event() {
     match event.event {
         Some(vcm_proto::event::Event::StateChange(change)) => match change.status {
             Some(vcm_proto::state_change::Status::NewState(state)) => {
-                assert_eq!(state, State::Paused.into());
+                assert_eq!(state, i32::from(State::Paused));
             }
             _ => panic!("Expected NewState"),
         },
         _ => panic!("Expected StateChange event"),
     }
 }
+
+#[tokio::test]
+async fn test_apply_config_updates_runtime_settings() {
+    let (controller, _) = create_controller();
+    let mut config = Config::default();
+    config.model.language = "cs".to_string();
+    config.vad.threshold = 0.7;
+    config.vad.chunk_size = 1024;
+    config.latency.mode = LatencyMode::Fast;
+    config.model.task = Task::Translate;
+
+    controller.apply_config(config).await.unwrap();
+
+    let (language, _) = controller.get_language_info().await;
+    assert_eq!(language, "cs");
+    let settings = controller.shared_settings.lock().unwrap().clone();
+    assert!((settings.vad.threshold - 0.7).abs() < f32::EPSILON);
+    assert_eq!(settings.vad.chunk_size, 1024);
+    assert_eq!(settings.latency.mode, LatencyMode::Fast);
+    assert_eq!(settings.task, Task::Translate);
+    assert_eq!(controller.task().await, Task::Translate);
+}
+
+#[tokio::test]
+async fn test_language_name_converted_to_code() {
+    let (controller, _) = create_controller();
+    let mut config = Config::default();
+    config.model.language = "Slovak".to_string();
+
+    controller.apply_config(config).await.unwrap();
+
+    let (language, _) = controller.get_language_info().await;
+    assert_eq!(language, "sk");
+}
+
+#[tokio::test]
+async fn test_set_language_rejects_unknown_language() {
+    let (controller, _) = create_controller();
+    let err = controller.set_language("klingon").await.unwrap_err();
+    assert!(err.contains("klingon"));
+    let (language, _) = controller.get_language_info().await;
+    assert_eq!(language, "auto");
+}
+
+#[tokio::test]
+async fn test_apply_config_rejects_invalid_vad() {
+    let (controller, _) = create_controller();
+    let mut config = Config::default();
+    config.vad.chunk_size = 100;
+
+    assert!(controller.apply_config(config).await.is_err());
+    assert_eq!(
+        controller.shared_settings.lock().unwrap().vad.chunk_size,
+        512
+    );
+}
+
+#[tokio::test]
+async fn test_set_model_fails_during_initializing() {
+    let (controller, _) = create_controller();
+    let result = controller.set_model(SpeechModel::WhisperSmall).await;
+    assert_eq!(result.unwrap_err(), "Daemon is still initializing");
+}
+
+#[tokio::test]
+async fn test_set_model_to_current_model_reports_ready() {
+    let (controller, _) = create_controller();
+    controller.mark_ready().await;
+    let mut rx = controller.event_sender().subscribe();
+
+    controller
+        .set_model(SpeechModel::WhisperBase)
+        .await
+        .unwrap();
+
+    let event = rx.try_recv().unwrap();
+    match event.event {
+        Some(vcm_proto::event::Event::ModelSwitch(switch)) => {
+            assert_eq!(switch.model_name, "whisper-base");
+            assert!(matches!(
+                switch.progress,
+                Some(vcm_proto::model_switch::Progress::Ready(_))
+            ));
+        }
+        _ => panic!("Expected ModelSwitch event"),
+    }
+    assert!(!controller.switching_model.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_set_model_rejects_english_only_model_when_translating() {
+    let (controller, _) = create_controller();
+    let mut config = Config::default();
+    config.model.task = Task::Translate;
+    controller.apply_config(config).await.unwrap();
+    controller.mark_ready().await;
+
+    let err = controller
+        .set_model(SpeechModel::WhisperBaseEn)
+        .await
+        .unwrap_err();
+    assert!(err.contains("multilingual"));
+    assert_eq!(controller.model().await, SpeechModel::WhisperBase);
+}
+
+#[test]
+fn test_transcription_event_carries_statistics() {
+    let transcript = Transcript {
+        text: "Hello world.".to_string(),
+        raw_text: Some("hello world period".to_string()),
+        avg_logprob: -0.25,
+        no_speech_prob: 0.1,
+        language: Some("en".to_string()),
+        language_probability: Some(0.9),
+        translated: false,
+        segments: vec![crate::transcribe::TranscriptSegment {
+            start_ms: 0,
+            end_ms: 1200,
+            text: "hello world".to_string(),
+        }],
+    };
+    let confidence = transcript.confidence();
+
+    let Some(vcm_proto::event::Event::Transcription(event)) =
+        transcription_event(transcript, true).event
+    else {
+        panic!("expected a transcription event");
+    };
+    assert_eq!(event.text, "Hello world.");
+    assert_eq!(event.raw_text, "hello world period");
+    assert!(event.is_partial);
+    assert!((event.confidence - f64::from(confidence)).abs() < 1e-9);
+    assert!((event.avg_logprob + 0.25).abs() < 1e-9);
+    assert_eq!(event.language, "en");
+    assert!((event.language_probability - 0.9).abs() < 1e-6);
+    assert_eq!(event.segments.len(), 1);
+    assert_eq!(event.segments[0].end_ms, 1200);
+}
+
+#[test]
+fn test_transcription_event_raw_text_defaults_to_text() {
+    let Some(vcm_proto::event::Event::Transcription(event)) =
+        transcription_event(Transcript::from_text("hello"), false).event
+    else {
+        panic!("expected a transcription event");
+    };
+    assert_eq!(event.raw_text, "hello");
+}
+
+#[tokio::test]
+async fn test_reload_after_idle_unload_reports_initializing() {
+    let (event_tx, mut event_rx) = broadcast::channel(64);
+    let (shutdown_tx, _) = oneshot::channel();
+    let mut config = Config::default();
+    config.daemon.idle_unload_secs = 1;
+    config.daemon.initial_state = InitialState::Paused;
+    let mut engine = Engine::new(config.clone()).unwrap();
+    engine.set_model_override(crate::engine::ModelOverride::scripted(
+        crate::transcribe::ScriptedTranscriber::new(["Hello."]),
+    ));
+    engine.set_audio_source(Box::new(crate::audio::ScriptedSource::new(
+        Vec::new(),
+        16000,
+    )));
+    engine.initialize(|_| {}).await.unwrap();
+    let recorder = crate::inject::RecordingInjector::new();
+    let controller = Controller::new(event_tx, shutdown_tx, engine, config)
+        .with_injector(crate::inject::recording_injector_factory(&recorder));
+
+    controller.mark_ready().await;
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(controller.models_unloaded());
+
+    controller.start_listening().await.unwrap();
+    assert!(!controller.models_unloaded());
+
+    let mut states = Vec::new();
+    while let Ok(event) = event_rx.try_recv() {
+        if let Some(vcm_proto::event::Event::StateChange(change)) = event.event
+            && let Some(vcm_proto::state_change::Status::NewState(state)) = change.status
+        {
+            states.push(State::try_from(state).unwrap());
+        }
+    }
+    assert_eq!(
+        states,
+        vec![State::Paused, State::Initializing, State::Listening]
+    );
+}
@@ -12,6 +12,7 @@ use vcm_proto::{DaemonError, ErrorKind, Event, InitProgress, ModelDownload, Mode
 use crate::config::Config;
 use crate::controller::Controller;
 use crate::engine::{Engine, InitEvent};
+use crate::inject::{InjectorFactory, keystroke_injector_factory};
 use crate::server::VcmService;
 use crate::socket::{cleanup_socket, create_listener};
 
@@ -44,6 +45,18 @@ pub async fn run_with_paths(paths: DaemonPaths) -> Result<()> {
 
 /// Run the daemon with custom paths and config (for testing).
 pub async fn run_with_paths_and_config(paths: DaemonPaths, config: Config) -> Result<()> {
+    let engine = Engine::new(config.clone()).context("Failed to create engine")?;
+    run_with_engine(paths, config, engine, keystroke_injector_factory()).await
+}
+
+/// Run the daemon with a prepared engine, delivering transcriptions through
+/// `injector` (for testing the pipeline without models or a keyboard).
+pub async fn run_with_engine(
+    paths: DaemonPaths,
+    config: Config,
+    engine: Engine,
+    injector: InjectorFactory,
+) -> Result<()> {
     let sock_path = paths.socket;
     let pid_file = paths.pid;
 
@@ -64,16 +77,11 @@ pub async fn run_with_paths_and_config(paths: DaemonPaths, config: Config) -> Re
     // Create event channel
     let (event_tx, _) = broadcast::channel(256);
 
-    // Create engine
-    let engine = Engine::new(config.clone()).context("Failed to create engine")?;
-
     // Create controller (starts in Initializing state)
-    let controller = Arc::new(Controller::new(
-        event_tx.clone(),
-        shutdown_tx,
-        engine,
-        config.clone(),
-    ));
+    let controller = Arc::new(
+        Controller::new(event_tx.clone(), shutdown_tx, engine, config.clone())
+            .with_injector(injector),
+    );
 
     // Create gRPC service
     let service = VcmService::new(controller.clone());
@@ -117,7 +125,7 @@ pub async fn run_with_paths_and_config(paths: DaemonPaths, config: Config) -> Re
 }
 
 /// Convert an engine InitEvent to a proto Event.
-fn init_event_to_proto(event: InitEvent) -> Event {
+pub(crate) fn init_event_to_proto(event: InitEvent) -> Event {
     let progress = match event {
         InitEvent::Loading { model } => {
             vcm_proto::init_progress::Progress::ModelLoad(ModelLoad { model_name: model })
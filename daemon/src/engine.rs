@@ -1,19 +1,30 @@
 //! Transcription engine that coordinates the audio pipeline.
 //!
 //! The engine owns and orchestrates:
-//! - Audio capture from microphone
+//! - Audio input (microphone, file, stdin or an injected source)
 //! - Resampling to 16kHz
 //! - Voice activity detection
-//! - Speech-to-text transcription
+//! - Speech-to-text transcription on a dedicated worker thread
 
-use crate::audio::{AudioCapture, AudioResampler, TARGET_SAMPLE_RATE};
-use crate::config::{Config, SpeechModel};
+use crate::audio::{AudioResampler, AudioSource, TARGET_SAMPLE_RATE};
+use crate::config::{
+    AudioConfig, Backend, Config, FilterConfig, LatencyConfig, PostprocessConfig, PunctuationMode,
+    SpeechModel, StreamingConfig, Task, VadConfig, VocabularyConfig, WhisperConfig,
+};
 use crate::models::{ModelId, ModelManager};
-use crate::transcribe::{Transcriber, WhisperTranscriber};
-use crate::vad::{VAD_SAMPLE_RATE, VadConfig, VadEvent, VoiceActivityDetector};
+use crate::postprocess::{TextPipeline, has_spoken_commands};
+use crate::segment::{SpeechSegment, SpeechSegmenter, ms_to_samples};
+use crate::transcribe::{
+    DecodingOptions, DroppedSegments, FilterReason, HallucinationFilter, HttpTranscriber, JobKind,
+    JobOutput, ProcessTranscriber, ScriptedTranscriber, Transcriber, Transcript, TranscriptionJob,
+    TranscriptionWorker, Vocabulary, WhisperTranscriber,
+};
+use crate::vad::{EnergyDetector, SpeechDetector, VAD_SAMPLE_RATE, VoiceActivityDetector};
 use anyhow::{Context, Result};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
@@ -22,6 +33,82 @@ use tracing::{debug, error, info, warn};
 /// `None` means auto-detect; `Some("en")` etc. means a specific language.
 pub type SharedLanguage = Arc<Mutex<Option<String>>>;
 
+/// Settings the engine loop picks up while running.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveSettings {
+    pub vad: VadConfig,
+    pub latency: LatencyConfig,
+    pub task: Task,
+    pub whisper: WhisperConfig,
+    pub filter: FilterConfig,
+    pub postprocess: PostprocessConfig,
+    pub streaming: StreamingConfig,
+    /// Language codes auto-detection may choose from; empty allows any.
+    pub candidate_languages: Vec<String>,
+    pub vocabulary: VocabularyConfig,
+}
+
+impl LiveSettings {
+    /// Take the live-reloadable sections from `config`.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            vad: config.vad.clone(),
+            latency: config.latency.clone(),
+            task: config.model.task,
+            whisper: config.whisper.clone(),
+            filter: config.filter.clone(),
+            postprocess: config.postprocess.clone(),
+            streaming: config.streaming.clone(),
+            candidate_languages: config.candidate_languages(),
+            vocabulary: config.vocabulary.clone(),
+        }
+    }
+}
+
+/// Shared live settings that can be updated from outside the engine loop.
+///
+/// The engine picks up changes between audio chunks.
+pub type SharedSettings = Arc<Mutex<LiveSettings>>;
+
+/// A transcriber waiting to replace the one in use.
+///
+/// The controller fills it after loading a new model; the engine hands it to
+/// the transcription worker between audio chunks.
+pub type SharedTranscriber = Arc<Mutex<Option<Box<dyn Transcriber>>>>;
+
+/// Builds the speech detector for the given live settings.
+pub type DetectorFactory =
+    Arc<dyn Fn(&LiveSettings) -> Result<Box<dyn SpeechDetector>> + Send + Sync>;
+
+/// Creates a transcriber.
+pub type TranscriberFactory = Arc<dyn Fn() -> Result<Box<dyn Transcriber>> + Send + Sync>;
+
+/// Models `initialize()` uses instead of downloading and loading the
+/// configured ones, so the pipeline runs without model files, e.g. in tests.
+#[derive(Clone)]
+pub struct ModelOverride {
+    pub detector: DetectorFactory,
+    pub transcriber: TranscriberFactory,
+}
+
+/// RMS level from which [`ModelOverride::scripted`] detects speech.
+const SCRIPTED_RMS_THRESHOLD: f32 = 0.01;
+
+impl ModelOverride {
+    /// Detect speech by loudness and answer with `transcriber`'s script.
+    pub fn scripted(transcriber: ScriptedTranscriber) -> Self {
+        Self {
+            detector: Arc::new(|settings: &LiveSettings| {
+                Ok(Box::new(EnergyDetector::new(
+                    SCRIPTED_RMS_THRESHOLD,
+                    settings.vad.chunk_size,
+                )))
+            }),
+            transcriber: Arc::new(move || Ok(Box::new(transcriber.clone()))),
+        }
+    }
+}
+
 /// Events emitted during engine initialization.
 #[derive(Debug, Clone)]
 pub enum InitEvent {
@@ -37,11 +124,54 @@ pub enum InitEvent {
     Ready,
 }
 
+/// Events emitted while the engine loop is running.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineEvent {
+    /// A speech segment was transcribed.
+    ///
+    /// When partials were sent for the segment, this is its final transcript
+    /// and its text may be empty if the segment turned out to be noise.
+    Transcription(Transcript),
+    /// The speech segment in progress was re-transcribed (streaming mode).
+    Partial(Transcript),
+    /// Text was removed from a final transcription as hallucinated.
+    Filtered(FilterReason),
+    /// The audio stream failed; the engine keeps listening and tries to reopen it.
+    AudioError(String),
+    /// Audio capture was reopened after an error.
+    AudioRecovered,
+    /// Transcription fell too far behind and speech segments were dropped.
+    SegmentsDropped(DroppedSegments),
+}
+
 /// Loaded model components ready for audio processing.
 struct InitializedComponents {
-    vad: VoiceActivityDetector,
-    transcriber: WhisperTranscriber,
+    vad: Box<dyn SpeechDetector>,
+    /// Rebuilds `vad` when its chunk size changes.
+    detector_factory: DetectorFactory,
+    /// Last settings picked up from `SharedSettings`.
+    settings: LiveSettings,
+    segmenter: SpeechSegmenter,
+    worker: TranscriptionWorker,
+    filter: HallucinationFilter,
+    postprocess: TextPipeline,
     shared_language: SharedLanguage,
+    partial: PartialState,
+}
+
+/// Finished segments that can wait for the transcription worker before
+/// queueing blocks the audio loop.
+const TRANSCRIPTION_QUEUE_CAPACITY: usize = 8;
+
+/// Streaming progress for the speech segment in progress.
+#[derive(Debug, Default)]
+struct PartialState {
+    /// Samples pushed since the last partial was queued.
+    since_last: usize,
+    /// A partial was queued for the segment in progress.
+    queued: bool,
+    /// A partial was emitted since the last final transcription.
+    emitted: bool,
 }
 
 /// Transcription engine.
@@ -50,40 +180,59 @@ pub struct Engine {
     model_manager: ModelManager,
     components: Option<InitializedComponents>,
     shared_language: SharedLanguage,
+    shared_settings: SharedSettings,
+    pending_transcriber: SharedTranscriber,
+    audio_source: Option<Box<dyn AudioSource>>,
+    model_override: Option<ModelOverride>,
 }
 
 impl Engine {
     /// Create a new engine with the given configuration.
     pub fn new(config: Config) -> Result<Self> {
         let model_manager = ModelManager::new()?;
-        let language = if config.model.language == "auto" {
-            None
-        } else {
-            Some(config.model.language.clone())
-        };
+        let language = config.model.language_code();
+        let shared_settings = Arc::new(Mutex::new(LiveSettings::from_config(&config)));
         Ok(Self {
             config,
             model_manager,
             components: None,
+            shared_settings,
             shared_language: Arc::new(Mutex::new(language)),
+            pending_transcriber: Arc::new(Mutex::new(None)),
+            audio_source: None,
+            model_override: None,
         })
     }
 
     /// Create a new engine with a custom model manager.
     pub fn with_model_manager(config: Config, model_manager: ModelManager) -> Self {
-        let language = if config.model.language == "auto" {
-            None
-        } else {
-            Some(config.model.language.clone())
-        };
+        let language = config.model.language_code();
+        let shared_settings = Arc::new(Mutex::new(LiveSettings::from_config(&config)));
         Self {
             config,
             model_manager,
             components: None,
+            shared_settings,
             shared_language: Arc::new(Mutex::new(language)),
+            pending_transcriber: Arc::new(Mutex::new(None)),
+            audio_source: None,
+            model_override: None,
         }
     }
 
+    /// Use `source` for the next `run_loop()` instead of opening one from config.
+    ///
+    /// The source is consumed by that run; later runs fall back to `[audio]` config.
+    pub fn set_audio_source(&mut self, source: Box<dyn AudioSource>) {
+        self.audio_source = Some(source);
+    }
+
+    /// Use `models` in every later `initialize()` instead of the configured
+    /// models.
+    pub fn set_model_override(&mut self, models: ModelOverride) {
+        self.model_override = Some(models);
+    }
+
     /// Get a handle to the shared language state.
     ///
     /// The controller uses this to update the language at runtime.
@@ -92,6 +241,57 @@ impl Engine {
         Arc::clone(&self.shared_language)
     }
 
+    /// Get a handle to the shared live settings.
+    ///
+    /// The controller updates it on config reload; the running loop rebuilds
+    /// its detector and segmenter when it changes.
+    pub fn shared_settings(&self) -> SharedSettings {
+        Arc::clone(&self.shared_settings)
+    }
+
+    /// Get a handle to the slot for a replacement transcriber.
+    ///
+    /// The running loop picks it up between audio chunks; while paused, call
+    /// [`apply_pending_transcriber`](Self::apply_pending_transcriber).
+    pub fn pending_transcriber(&self) -> SharedTranscriber {
+        Arc::clone(&self.pending_transcriber)
+    }
+
+    /// Switch to the transcriber waiting in the pending slot, if any.
+    ///
+    /// When the models are not loaded the waiting transcriber is dropped;
+    /// `initialize()` loads the configured model instead.
+    pub fn apply_pending_transcriber(&mut self) {
+        match self.components.as_mut() {
+            Some(components) => replace_transcriber(components, &self.pending_transcriber),
+            None => {
+                if let Ok(mut slot) = self.pending_transcriber.lock() {
+                    slot.take();
+                }
+            }
+        }
+    }
+
+    /// Set the speech model `initialize()` loads.
+    pub fn set_model(&mut self, model: SpeechModel) {
+        self.config.model.model = model;
+    }
+
+    /// Free the loaded models. Call `initialize()` before the next `run_loop()`.
+    pub fn unload(&mut self) {
+        if self.components.take().is_some() {
+            info!("Models unloaded");
+        }
+        if let Ok(mut slot) = self.pending_transcriber.lock() {
+            slot.take();
+        }
+    }
+
+    /// Get the model manager the engine downloads models with.
+    pub fn model_manager(&self) -> &ModelManager {
+        &self.model_manager
+    }
+
     /// Check if the engine has been initialized (models loaded).
     pub fn is_initialized(&self) -> bool {
         self.components.is_some()
@@ -101,49 +301,48 @@ impl Engine {
     ///
     /// Calls `on_progress` with status updates suitable for UI display.
     /// After this returns Ok(()), the engine is ready for `run_loop()`.
-    pub async fn initialize(&mut self, on_progress: impl Fn(InitEvent) + Send) -> Result<()> {
+    pub async fn initialize(
+        &mut self,
+        on_progress: impl Fn(InitEvent) + Send + Sync,
+    ) -> Result<()> {
         info!("Initializing engine");
 
-        // Ensure VAD model
-        on_progress(InitEvent::Loading {
-            model: "silero-vad".to_string(),
-        });
-        let vad_model_path = self
-            .model_manager
-            .ensure_model(ModelId::SileroVad)
-            .await
-            .context("Failed to ensure VAD model")?;
-
-        // Ensure Whisper model
-        let whisper_model_id = speech_model_to_model_id(self.config.model.model);
-        on_progress(InitEvent::Loading {
-            model: whisper_model_id.to_string(),
-        });
-        let whisper_model_path = self
-            .model_manager
-            .ensure_model(whisper_model_id)
-            .await
-            .context("Failed to ensure Whisper model")?;
+        let (detector_factory, transcriber) = match &self.model_override {
+            Some(models) => {
+                info!("Using model override");
+                (
+                    Arc::clone(&models.detector),
+                    (models.transcriber)().context("Failed to create transcriber")?,
+                )
+            }
+            None => load_models(&self.model_manager, &self.config, &on_progress).await?,
+        };
 
         info!("Models ready, initializing components");
 
         // Initialize VAD
-        let vad = VoiceActivityDetector::new(&vad_model_path, VadConfig::default())
-            .context("Failed to initialize VAD")?;
-
-        // Initialize transcriber
-        let language = if self.config.model.language == "auto" {
-            None
-        } else {
-            Some(self.config.model.language.clone())
-        };
-        let transcriber = WhisperTranscriber::new(&whisper_model_path, language)
-            .context("Failed to initialize Whisper")?;
+        let settings = self
+            .shared_settings
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to lock live settings: {e}"))?
+            .clone();
+        let vad = detector_factory(&settings)?;
+        let segmenter = build_segmenter(&settings);
+        let filter = HallucinationFilter::new(&settings.filter);
+        let postprocess = TextPipeline::new(&settings.postprocess);
+
+        let worker = TranscriptionWorker::spawn(transcriber, TRANSCRIPTION_QUEUE_CAPACITY)?;
 
         self.components = Some(InitializedComponents {
             vad,
-            transcriber,
+            detector_factory,
+            settings,
+            segmenter,
+            worker,
+            filter,
+            postprocess,
             shared_language: Arc::clone(&self.shared_language),
+            partial: PartialState::default(),
         });
 
         on_progress(InitEvent::Ready);
@@ -154,36 +353,35 @@ impl Engine {
 
     /// Run the audio capture and transcription loop.
     ///
-    /// Blocks until the `cancel` token is cancelled.
+    /// Blocks until the `cancel` token is cancelled, or until a finite audio
+    /// source (file, stdin, scripted) is exhausted. Speech segments still
+    /// queued for transcription are transcribed before returning. If the audio stream fails,
+    /// emits [`EngineEvent::AudioError`] and keeps reopening the configured
+    /// source with backoff until it comes back or the loop is cancelled.
     /// Requires `initialize()` to have been called first.
     pub async fn run_loop(
         &mut self,
         cancel: CancellationToken,
-        mut on_transcription: impl FnMut(&str),
+        mut on_event: impl FnMut(EngineEvent),
     ) -> Result<()> {
         let components = self
             .components
             .as_mut()
             .context("Engine not initialized — call initialize() first")?;
 
-        info!("Starting audio capture");
-
-        let capture = AudioCapture::start().context("Failed to start audio capture")?;
-        let sample_rate = capture.sample_rate();
-        info!(
-            sample_rate = sample_rate,
-            target_rate = TARGET_SAMPLE_RATE,
-            "Audio capture started"
-        );
+        info!(source = ?self.config.audio.source, "Starting audio capture");
 
-        let mut resampler = AudioResampler::new(sample_rate, TARGET_SAMPLE_RATE, 1024)
-            .context("Failed to create resampler")?;
+        let source = match self.audio_source.take() {
+            Some(source) => source,
+            None => crate::audio::open_source(&self.config.audio)
+                .context("Failed to start audio capture")?,
+        };
+        let mut input = AudioInput::new(source)?;
 
         let mut audio = AudioBuffers {
             input: Vec::new(),
             vad: Vec::new(),
-            speech: Vec::new(),
-            resampler_chunk: resampler.chunk_size(),
+            resampler_chunk: input.resampler.chunk_size(),
             vad_chunk: components.vad.chunk_size(),
         };
 
@@ -196,24 +394,58 @@ impl Engine {
                     break;
                 }
                 _ = tokio::time::sleep(std::time::Duration::from_millis(10)) => {
-                    if let Some(samples) = capture.try_recv() {
+                    emit_results(components, &mut on_event);
+                    apply_settings(components, &self.shared_settings, &mut audio);
+                    replace_transcriber(components, &self.pending_transcriber);
+
+                    if let Some(samples) = input.source.try_recv() {
                         audio.input.extend(samples);
-                        resample_input(&mut audio, &mut resampler);
-                        process_vad_chunks(components, &mut audio, &mut on_transcription);
+                        resample_input(&mut audio, &mut input.resampler);
+                        process_vad_chunks(components, &mut audio);
+                    } else if input.source.is_finished() {
+                        info!("Audio source finished");
+                        flush_audio(components, &mut audio, &mut input.resampler);
+                        break;
+                    } else if let Some(err) = input.source.poll_error() {
+                        error!(error = %err, "Audio stream failed");
+                        on_event(EngineEvent::AudioError(err));
+                        input.stop();
+                        audio.reset();
+                        components.vad.reset();
+                        components.segmenter.reset();
+                        components.partial.since_last = 0;
+                        components.partial.queued = false;
+
+                        let Some(reopened) = reopen_with_backoff(
+                            &self.config.audio,
+                            &cancel,
+                            components,
+                            &mut on_event,
+                        )
+                        .await
+                        else {
+                            drain_results(components, &mut on_event).await;
+                            return Ok(());
+                        };
+                        input = reopened;
+                        audio.resampler_chunk = input.resampler.chunk_size();
+                        on_event(EngineEvent::AudioRecovered);
                     }
                 }
             }
         }
 
-        capture.stop();
+        input.stop();
         info!("Audio capture stopped");
 
+        drain_results(components, &mut on_event).await;
+
         Ok(())
     }
 
     /// Run the full pipeline (initialize + loop). Convenience for examples/tests.
     #[deprecated(note = "prefer calling initialize() + run_loop() separately")]
-    pub async fn run<F>(&mut self, running: Arc<AtomicBool>, on_transcription: F) -> Result<()>
+    pub async fn run<F>(&mut self, running: Arc<AtomicBool>, mut on_transcription: F) -> Result<()>
     where
         F: FnMut(&str),
     {
@@ -233,7 +465,87 @@ impl Engine {
             }
         });
 
-        self.run_loop(cancel, on_transcription).await
+        self.run_loop(cancel, move |event| {
+            if let EngineEvent::Transcription(transcript) = event {
+                on_transcription(&transcript.text);
+            }
+        })
+        .await
+    }
+}
+
+/// An audio source paired with the resampler matching its sample rate.
+struct AudioInput {
+    source: Box<dyn AudioSource>,
+    resampler: AudioResampler,
+}
+
+impl AudioInput {
+    fn new(source: Box<dyn AudioSource>) -> Result<Self> {
+        let sample_rate = source.sample_rate();
+        info!(
+            sample_rate = sample_rate,
+            target_rate = TARGET_SAMPLE_RATE,
+            "Audio capture started"
+        );
+        let resampler = AudioResampler::new(sample_rate, TARGET_SAMPLE_RATE, 1024)
+            .context("Failed to create resampler")?;
+        Ok(Self { source, resampler })
+    }
+
+    fn stop(self) {
+        self.source.stop();
+    }
+}
+
+/// First delay between attempts to reopen a failed audio source.
+const REOPEN_INITIAL_DELAY: Duration = Duration::from_millis(500);
+
+/// Upper bound for the reopen backoff.
+const REOPEN_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Double the reopen delay, capped at [`REOPEN_MAX_DELAY`].
+fn next_reopen_delay(delay: Duration) -> Duration {
+    (delay * 2).min(REOPEN_MAX_DELAY)
+}
+
+/// Keep trying to reopen the configured audio source, doubling the delay
+/// between attempts, and emit finished transcriptions meanwhile. Returns
+/// `None` if cancelled first.
+async fn reopen_with_backoff(
+    config: &AudioConfig,
+    cancel: &CancellationToken,
+    components: &mut InitializedComponents,
+    on_event: &mut impl FnMut(EngineEvent),
+) -> Option<AudioInput> {
+    let mut delay = REOPEN_INITIAL_DELAY;
+    loop {
+        let retry = tokio::time::sleep(delay);
+        tokio::pin!(retry);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => {
+                    info!("Cancellation received while reopening audio source");
+                    return None;
+                }
+                _ = &mut retry => break,
+                // Speech captured before the failure is still typed
+                _ = tokio::time::sleep(Duration::from_millis(10)) => {
+                    emit_results(components, on_event);
+                }
+            }
+        }
+
+        match crate::audio::open_source(config).and_then(AudioInput::new) {
+            Ok(input) => {
+                info!("Audio source reopened");
+                return Some(input);
+            }
+            Err(e) => {
+                delay = next_reopen_delay(delay);
+                warn!(error = %e, retry_in = ?delay, "Failed to reopen audio source");
+            }
+        }
     }
 }
 
@@ -241,11 +553,86 @@ impl Engine {
 struct AudioBuffers {
     input: Vec<f32>,
     vad: Vec<f32>,
-    speech: Vec<f32>,
     resampler_chunk: usize,
     vad_chunk: usize,
 }
 
+impl AudioBuffers {
+    /// Drop all buffered audio, e.g. after the stream failed mid-utterance.
+    fn reset(&mut self) {
+        self.input.clear();
+        self.vad.clear();
+    }
+}
+
+/// Load the VAD model for the given settings.
+fn build_vad(model_path: &Path, settings: &LiveSettings) -> Result<VoiceActivityDetector> {
+    VoiceActivityDetector::with_chunk_size(
+        model_path,
+        settings.vad.detector_config(settings.latency.mode),
+        settings.vad.chunk_size,
+    )
+    .context("Failed to initialize VAD")
+}
+
+/// Create a segmenter for the given settings.
+fn build_segmenter(settings: &LiveSettings) -> SpeechSegmenter {
+    let max_segment_secs = settings.vad.max_segment_secs(settings.latency.mode);
+    SpeechSegmenter::new(
+        settings.vad.detector_config(settings.latency.mode),
+        ms_to_samples(settings.vad.pre_roll_ms),
+        ms_to_samples(settings.vad.post_roll_ms),
+    )
+    .with_max_len((max_segment_secs * VAD_SAMPLE_RATE as f32) as usize)
+}
+
+/// Pick up live settings changed since the last call.
+///
+/// When VAD or latency settings change, speech in progress is transcribed
+/// first and the segmenter is rebuilt; the VAD model is only reloaded when
+/// the chunk size changes. Other settings apply to the next transcription.
+fn apply_settings(
+    components: &mut InitializedComponents,
+    shared: &SharedSettings,
+    audio: &mut AudioBuffers,
+) {
+    let settings = match shared.lock() {
+        Ok(settings) if *settings != components.settings => settings.clone(),
+        _ => return,
+    };
+
+    if settings.filter != components.settings.filter {
+        components.filter = HallucinationFilter::new(&settings.filter);
+    }
+    if settings.postprocess != components.settings.postprocess {
+        components.postprocess = TextPipeline::new(&settings.postprocess);
+    }
+
+    if settings.vad == components.settings.vad && settings.latency == components.settings.latency {
+        components.settings = settings;
+        info!("Live settings applied");
+        return;
+    }
+
+    if let Some(segment) = components.segmenter.flush() {
+        transcribe_segment(components, segment);
+    }
+
+    if settings.vad.chunk_size != components.vad.chunk_size() {
+        match (components.detector_factory)(&settings) {
+            Ok(vad) => components.vad = vad,
+            Err(e) => error!(error = %e, "Failed to apply VAD chunk size, keeping previous one"),
+        }
+        audio.vad_chunk = components.vad.chunk_size();
+    } else {
+        components.vad.reset();
+    }
+
+    components.segmenter = build_segmenter(&settings);
+    components.settings = settings;
+    info!("Live settings applied");
+}
+
 /// Drain complete chunks from the input buffer and resample into the VAD buffer.
 fn resample_input(audio: &mut AudioBuffers, resampler: &mut AudioResampler) {
     while audio.input.len() >= audio.resampler_chunk {
@@ -256,85 +643,381 @@ fn resample_input(audio: &mut AudioBuffers, resampler: &mut AudioResampler) {
     }
 }
 
-/// Process complete VAD-sized chunks, detecting speech boundaries and transcribing.
-fn process_vad_chunks(
+/// Drain what is left once a finite source ends: pad the tail up to whole
+/// chunks, run it through VAD and queue any speech still in progress.
+fn flush_audio(
     components: &mut InitializedComponents,
     audio: &mut AudioBuffers,
-    on_transcription: &mut impl FnMut(&str),
+    resampler: &mut AudioResampler,
 ) {
+    if !audio.input.is_empty() {
+        let padded = audio.input.len().next_multiple_of(audio.resampler_chunk);
+        audio.input.resize(padded, 0.0);
+        resample_input(audio, resampler);
+    }
+    if !audio.vad.is_empty() {
+        let padded = audio.vad.len().next_multiple_of(audio.vad_chunk);
+        audio.vad.resize(padded, 0.0);
+    }
+    process_vad_chunks(components, audio);
+
+    if let Some(segment) = components.segmenter.flush() {
+        transcribe_segment(components, segment);
+    }
+    components.vad.reset();
+}
+
+/// Process complete VAD-sized chunks, detecting speech boundaries and queueing
+/// segments for transcription.
+fn process_vad_chunks(components: &mut InitializedComponents, audio: &mut AudioBuffers) {
     while audio.vad.len() >= audio.vad_chunk {
         let chunk: Vec<f32> = audio.vad.drain(..audio.vad_chunk).collect();
 
-        if components.vad.is_speaking() {
-            audio.speech.extend(&chunk);
-        }
-
-        match components.vad.process(&chunk) {
-            Ok(Some(VadEvent::SpeechStart)) => {
-                debug!("Speech started");
-                audio.speech.clear();
-                audio.speech.extend(&chunk);
-            }
-            Ok(Some(VadEvent::SpeechEnd)) => {
-                transcribe_speech(components, audio, on_transcription);
-            }
-            Ok(None) => {}
+        let probability = match components.vad.process_chunk(&chunk) {
+            Ok(probability) => probability,
             Err(e) => {
                 warn!(error = %e, "VAD processing error");
+                continue;
+            }
+        };
+
+        if let Some(segment) = components.segmenter.push(&chunk, probability) {
+            transcribe_segment(components, segment);
+        } else if components.settings.streaming.enabled && components.segmenter.is_speaking() {
+            components.partial.since_last += chunk.len();
+            let interval = ms_to_samples(components.settings.streaming.interval_ms);
+            if components.partial.since_last >= interval {
+                transcribe_partial(components);
             }
         }
     }
 }
 
-/// Minimum speech duration in seconds to attempt transcription.
-/// Segments shorter than this are almost certainly noise/clicks, not real speech.
-const MIN_SPEECH_DURATION_SECS: f32 = 0.3;
+/// Queue the speech segment in progress for a partial transcription.
+///
+/// Skipped while the worker is busy, so partials never delay final results.
+fn transcribe_partial(components: &mut InitializedComponents) {
+    if !components.worker.is_idle() {
+        return;
+    }
+    let Some(segment) = components.segmenter.pending() else {
+        return;
+    };
+    let min_speech_secs = components.settings.latency.mode.profile().min_speech_secs;
+    if segment.speech_duration_secs() < min_speech_secs {
+        return;
+    }
 
-/// Transcribe accumulated speech and clear the buffer.
-fn transcribe_speech(
-    components: &mut InitializedComponents,
-    audio: &mut AudioBuffers,
-    on_transcription: &mut impl FnMut(&str),
-) {
-    let duration_secs = audio.speech.len() as f32 / VAD_SAMPLE_RATE as f32;
+    let samples = pad_to_min_duration(
+        segment.samples.clone(),
+        components.settings.latency.min_chunk_seconds,
+    );
+    let job = transcription_job(components, JobKind::Partial, samples);
+    if components.worker.try_submit(job).is_ok() {
+        components.partial.since_last = 0;
+        components.partial.queued = true;
+    }
+}
+
+/// Queue a finished speech segment for transcription.
+fn transcribe_segment(components: &mut InitializedComponents, segment: SpeechSegment) {
+    let partial_queued = std::mem::take(&mut components.partial.queued);
+    components.partial.since_last = 0;
+    let duration_secs = segment.speech_duration_secs();
     debug!(
-        samples = audio.speech.len(),
+        samples = segment.samples.len(),
+        pre_roll = segment.pre_roll,
         duration_secs = duration_secs,
-        "Speech ended, transcribing"
+        "Speech ended, queueing transcription"
     );
 
-    if duration_secs < MIN_SPEECH_DURATION_SECS {
+    // Segments shorter than this are almost certainly noise/clicks, not real speech.
+    let min_speech_secs = components.settings.latency.mode.profile().min_speech_secs;
+    let samples = if duration_secs < min_speech_secs {
         debug!(
             duration_secs = duration_secs,
-            min = MIN_SPEECH_DURATION_SECS,
+            min = min_speech_secs,
             "Skipping too-short speech segment"
         );
-        audio.speech.clear();
-        return;
+        // Clients showing a partial still need the (empty) final result
+        if !partial_queued {
+            return;
+        }
+        Vec::new()
+    } else {
+        pad_to_min_duration(
+            segment.samples,
+            components.settings.latency.min_chunk_seconds,
+        )
+    };
+
+    let job = transcription_job(components, JobKind::Final, samples);
+    if let Err(e) = components.worker.submit(job) {
+        error!(error = %e, "Failed to queue transcription");
     }
+}
+
+/// Build a job for 16kHz samples with the current language and settings.
+fn transcription_job(
+    components: &InitializedComponents,
+    kind: JobKind,
+    samples: Vec<f32>,
+) -> TranscriptionJob {
+    let language = components
+        .shared_language
+        .lock()
+        .map(|lang| lang.clone())
+        .unwrap_or_default();
+    let suppress_punctuation = suppress_punctuation(&components.settings, language.as_deref());
+    TranscriptionJob {
+        kind,
+        samples,
+        language,
+        candidate_languages: components.settings.candidate_languages.clone(),
+        vocabulary: current_vocabulary(&components.settings.vocabulary),
+        task: components.settings.task,
+        decoding: DecodingOptions {
+            suppress_punctuation,
+            ..components
+                .settings
+                .whisper
+                .decoding(components.settings.latency.mode)
+        },
+    }
+}
 
-    if !audio.speech.is_empty() {
-        // Sync language from shared state before transcription
-        if let Ok(lang) = components.shared_language.lock() {
-            components.transcriber.set_language(lang.clone());
+/// Whether the model should leave punctuation to spoken commands: only
+/// when they are on and every language the text may be in has them.
+/// Translations are in English.
+fn suppress_punctuation(settings: &LiveSettings, language: Option<&str>) -> bool {
+    if settings.postprocess.punctuation != PunctuationMode::Spoken {
+        return false;
+    }
+    if settings.task == Task::Translate {
+        return has_spoken_commands(Some("en"));
+    }
+    match language {
+        Some(language) => has_spoken_commands(Some(language)),
+        None => {
+            !settings.candidate_languages.is_empty()
+                && settings
+                    .candidate_languages
+                    .iter()
+                    .all(|language| has_spoken_commands(Some(language)))
         }
+    }
+}
 
-        match components
-            .transcriber
-            .transcribe(&audio.speech, VAD_SAMPLE_RATE)
-        {
-            Ok(text) => {
-                if !text.is_empty() {
-                    info!(text = %text, "Transcription complete");
-                    on_transcription(&text);
-                }
+/// Resolve the vocabulary for the application in the foreground.
+fn current_vocabulary(vocabulary: &VocabularyConfig) -> Vocabulary {
+    if vocabulary.apps.is_empty() {
+        return vocabulary.resolve(None);
+    }
+    let app = vcm_platform::frontmost::current()
+        .inspect_err(|e| debug!(error = %e, "Failed to get frontmost app for vocabulary"))
+        .ok();
+    vocabulary.resolve(app.as_deref())
+}
+
+/// Emit results the transcription worker has finished so far.
+fn emit_results(components: &mut InitializedComponents, on_event: &mut impl FnMut(EngineEvent)) {
+    if let Some(dropped) = components.worker.take_dropped() {
+        on_event(EngineEvent::SegmentsDropped(dropped));
+    }
+    while let Some(output) = components.worker.try_recv() {
+        emit_result(
+            &mut components.partial,
+            &components.filter,
+            &components.postprocess,
+            output,
+            on_event,
+        );
+    }
+}
+
+/// Wait for every queued job and emit its result.
+async fn drain_results(
+    components: &mut InitializedComponents,
+    on_event: &mut impl FnMut(EngineEvent),
+) {
+    if !components.worker.is_idle() {
+        debug!(
+            pending = components.worker.pending(),
+            "Waiting for queued transcriptions"
+        );
+    }
+    loop {
+        emit_results(components, on_event);
+        if components.worker.is_idle() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    components.partial = PartialState::default();
+}
+
+/// Turn one worker result into an engine event, after removing
+/// hallucinations and post-processing the text.
+fn emit_result(
+    partial: &mut PartialState,
+    filter: &HallucinationFilter,
+    postprocess: &TextPipeline,
+    output: JobOutput,
+    on_event: &mut impl FnMut(EngineEvent),
+) {
+    match (output.kind, output.transcript) {
+        (JobKind::Partial, Ok(mut transcript)) => {
+            filter.apply(&mut transcript, output.audio_secs);
+            postprocess.apply(&mut transcript);
+            if !transcript.text.is_empty() {
+                debug!(text = %transcript.text, "Partial transcription");
+                partial.emitted = true;
+                on_event(EngineEvent::Partial(transcript));
             }
-            Err(e) => {
+        }
+        (JobKind::Partial, Err(e)) => warn!(error = %e, "Partial transcription failed"),
+        (JobKind::Final, result) => {
+            // Clients showing a partial need a final event even if nothing is transcribed
+            let partial_emitted = std::mem::take(&mut partial.emitted);
+            let mut transcript = result.unwrap_or_else(|e| {
                 error!(error = %e, "Transcription failed");
+                Transcript::default()
+            });
+            for reason in filter.apply(&mut transcript, output.audio_secs) {
+                on_event(EngineEvent::Filtered(reason));
+            }
+            postprocess.apply(&mut transcript);
+            if !transcript.text.is_empty() {
+                info!(
+                    text = %transcript.text,
+                    confidence = transcript.confidence(),
+                    "Transcription complete"
+                );
+                on_event(EngineEvent::Transcription(transcript));
+            } else if partial_emitted {
+                on_event(EngineEvent::Transcription(transcript));
             }
         }
     }
-    audio.speech.clear();
+}
+
+/// Append silence so `samples` lasts at least `min_secs` at 16kHz.
+fn pad_to_min_duration(mut samples: Vec<f32>, min_secs: f32) -> Vec<f32> {
+    let min_len = (min_secs * VAD_SAMPLE_RATE as f32) as usize;
+    if samples.len() < min_len {
+        samples.resize(min_len, 0.0);
+    }
+    samples
+}
+
+/// Download the configured models if needed and load the transcriber.
+async fn load_models(
+    model_manager: &ModelManager,
+    config: &Config,
+    on_progress: &(dyn Fn(InitEvent) + Send + Sync),
+) -> Result<(DetectorFactory, Box<dyn Transcriber>)> {
+    on_progress(InitEvent::Loading {
+        model: "silero-vad".to_string(),
+    });
+    let vad_model_path = model_manager
+        .ensure_model(ModelId::SileroVad)
+        .await
+        .context("Failed to ensure VAD model")?;
+
+    // Ensure and load Whisper model
+    let language = config.model.language_code();
+    let transcriber = load_transcriber(model_manager, config, language, on_progress).await?;
+
+    let detector_factory: DetectorFactory = Arc::new(move |settings: &LiveSettings| {
+        Ok(Box::new(build_vad(&vad_model_path, settings)?))
+    });
+    Ok((detector_factory, transcriber))
+}
+
+/// Create the transcriber for the configured backend.
+///
+/// The Whisper model in `config.model.model` is downloaded if needed and
+/// loaded, unless another backend runs without a local fallback. Reports
+/// download progress as [`InitEvent::Downloading`], at most once per
+/// percent, then [`InitEvent::Loading`]. Loading runs on a blocking thread.
+pub async fn load_transcriber(
+    model_manager: &ModelManager,
+    config: &Config,
+    language: Option<String>,
+    on_progress: &(dyn Fn(InitEvent) + Send + Sync),
+) -> Result<Box<dyn Transcriber>> {
+    match config.model.backend {
+        Backend::Whisper => {
+            load_whisper(model_manager, config.model.model, language, on_progress).await
+        }
+        Backend::Http => {
+            let fallback = if config.http.fallback {
+                Some(load_whisper(model_manager, config.model.model, language, on_progress).await?)
+            } else {
+                None
+            };
+            let http = config.http.clone();
+            let transcriber =
+                tokio::task::spawn_blocking(move || HttpTranscriber::new(&http, fallback))
+                    .await
+                    .context("HTTP client task failed")??;
+            Ok(Box::new(transcriber))
+        }
+        Backend::Process => {
+            let process = config.process.clone();
+            let transcriber =
+                tokio::task::spawn_blocking(move || ProcessTranscriber::new(&process))
+                    .await
+                    .context("Process starting task failed")??;
+            Ok(Box::new(transcriber))
+        }
+    }
+}
+
+/// Download Whisper `model` if needed and load it.
+async fn load_whisper(
+    model_manager: &ModelManager,
+    model: SpeechModel,
+    language: Option<String>,
+    on_progress: &(dyn Fn(InitEvent) + Send + Sync),
+) -> Result<Box<dyn Transcriber>> {
+    let model_id = speech_model_to_model_id(model);
+    let last_step = AtomicU64::new(u64::MAX);
+    let model_path = model_manager
+        .ensure_model_with_progress(model_id, &|bytes, total| {
+            // Without a known size, report every megabyte
+            let step = (bytes * 100).checked_div(total).unwrap_or(bytes >> 20);
+            if last_step.swap(step, Ordering::Relaxed) != step {
+                on_progress(InitEvent::Downloading {
+                    model: model_id.to_string(),
+                    bytes,
+                    total,
+                });
+            }
+        })
+        .await
+        .context("Failed to ensure Whisper model")?;
+
+    on_progress(InitEvent::Loading {
+        model: model_id.to_string(),
+    });
+    let transcriber =
+        tokio::task::spawn_blocking(move || WhisperTranscriber::new(&model_path, language))
+            .await
+            .context("Whisper loading task failed")?
+            .context("Failed to initialize Whisper")?;
+    Ok(Box::new(transcriber))
+}
+
+/// Hand the transcriber waiting in `pending`, if any, to the worker.
+fn replace_transcriber(components: &mut InitializedComponents, pending: &SharedTranscriber) {
+    let Some(transcriber) = pending.lock().ok().and_then(|mut slot| slot.take()) else {
+        return;
+    };
+    match components.worker.replace_transcriber(transcriber) {
+        Ok(()) => info!("Switched transcription model"),
+        Err(e) => error!(error = %e, "Failed to switch transcription model"),
+    }
 }
 
 /// Convert SpeechModel config to ModelId for download.
@@ -1,4 +1,5 @@
 use super::*;
+use crate::config::PostprocessStage;
 
 #[test]
 fn test_engine_not_initialized_by_default() {
@@ -7,6 +8,31 @@ fn test_engine_not_initialized_by_default() {
     assert!(!engine.is_initialized());
 }
 
+struct SilentTranscriber;
+
+impl Transcriber for SilentTranscriber {
+    fn transcribe(&mut self, _audio: &[f32], _sample_rate: u32) -> Result<Transcript> {
+        Ok(Transcript::default())
+    }
+
+    fn set_language(&mut self, _language: Option<String>) {}
+}
+
+#[test]
+fn test_pending_transcriber_dropped_while_unloaded() {
+    let mut engine = Engine::new(Config::default()).unwrap();
+    let pending = engine.pending_transcriber();
+
+    *pending.lock().unwrap() = Some(Box::new(SilentTranscriber));
+    engine.apply_pending_transcriber();
+    assert!(pending.lock().unwrap().is_none());
+
+    *pending.lock().unwrap() = Some(Box::new(SilentTranscriber));
+    engine.unload();
+    assert!(pending.lock().unwrap().is_none());
+    assert!(!engine.is_initialized());
+}
+
 #[test]
 fn test_speech_model_to_model_id() {
     assert_eq!(
@@ -18,3 +44,362 @@ fn test_speech_model_to_model_id() {
         ModelId::WhisperLargeV3Turbo
     );
 }
+
+#[test]
+fn test_reopen_delay_doubles_up_to_cap() {
+    assert_eq!(
+        next_reopen_delay(REOPEN_INITIAL_DELAY),
+        Duration::from_secs(1)
+    );
+    assert_eq!(next_reopen_delay(Duration::from_secs(8)), REOPEN_MAX_DELAY);
+    assert_eq!(next_reopen_delay(REOPEN_MAX_DELAY), REOPEN_MAX_DELAY);
+}
+
+#[test]
+fn test_pad_to_min_duration_appends_silence() {
+    let padded = pad_to_min_duration(vec![0.5; 8000], 1.0);
+    assert_eq!(padded.len(), 16000);
+    assert_eq!(padded[7999], 0.5);
+    assert_eq!(padded[8000], 0.0);
+}
+
+#[test]
+fn test_pad_to_min_duration_keeps_longer_audio() {
+    let samples = vec![0.5; 20000];
+    assert_eq!(pad_to_min_duration(samples.clone(), 1.0), samples);
+    assert_eq!(pad_to_min_duration(samples.clone(), 0.0), samples);
+}
+
+fn output(kind: JobKind, text: &str) -> JobOutput {
+    JobOutput {
+        kind,
+        transcript: Ok(Transcript::from_text(text)),
+        audio_secs: 2.0,
+    }
+}
+
+#[test]
+fn test_emit_result_sends_empty_final_only_after_partial() {
+    let mut partial = PartialState::default();
+    let filter = HallucinationFilter::default();
+    let postprocess = TextPipeline::default();
+    let mut events = Vec::new();
+    let mut on_event = |event| events.push(event);
+
+    emit_result(
+        &mut partial,
+        &filter,
+        &postprocess,
+        output(JobKind::Final, ""),
+        &mut on_event,
+    );
+    emit_result(
+        &mut partial,
+        &filter,
+        &postprocess,
+        output(JobKind::Partial, "hel"),
+        &mut on_event,
+    );
+    emit_result(
+        &mut partial,
+        &filter,
+        &postprocess,
+        output(JobKind::Final, ""),
+        &mut on_event,
+    );
+    emit_result(
+        &mut partial,
+        &filter,
+        &postprocess,
+        output(JobKind::Final, ""),
+        &mut on_event,
+    );
+
+    assert_eq!(
+        events,
+        vec![
+            EngineEvent::Partial(Transcript::from_text("hel")),
+            EngineEvent::Transcription(Transcript::default()),
+        ]
+    );
+}
+
+#[test]
+fn test_emit_result_reports_filtered_finals() {
+    let mut partial = PartialState::default();
+    let filter = HallucinationFilter::new(&FilterConfig::default());
+    let postprocess = TextPipeline::default();
+    let mut events = Vec::new();
+    let mut on_event = |event| events.push(event);
+
+    emit_result(
+        &mut partial,
+        &filter,
+        &postprocess,
+        output(JobKind::Partial, "Thank you for watching."),
+        &mut on_event,
+    );
+    emit_result(
+        &mut partial,
+        &filter,
+        &postprocess,
+        output(JobKind::Final, "Thank you for watching."),
+        &mut on_event,
+    );
+
+    assert_eq!(
+        events,
+        vec![EngineEvent::Filtered(FilterReason::Blocklisted)]
+    );
+}
+
+#[test]
+fn test_emit_result_post_processes_text() {
+    let mut partial = PartialState::default();
+    let filter = HallucinationFilter::default();
+    let postprocess = TextPipeline::new(&PostprocessConfig {
+        filter_words: vec!["um".to_string()],
+        ..PostprocessConfig::default()
+    });
+    let mut events = Vec::new();
+    let mut on_event = |event| events.push(event);
+
+    emit_result(
+        &mut partial,
+        &filter,
+        &postprocess,
+        output(JobKind::Final, "um ship it"),
+        &mut on_event,
+    );
+    // Nothing is left to type of a final that was all filler
+    emit_result(
+        &mut partial,
+        &filter,
+        &postprocess,
+        output(JobKind::Final, "Um."),
+        &mut on_event,
+    );
+
+    assert_eq!(
+        events,
+        vec![EngineEvent::Transcription(Transcript {
+            raw_text: Some("um ship it".to_string()),
+            ..Transcript::from_text("ship it")
+        })]
+    );
+}
+
+#[test]
+fn test_punctuation_suppressed_only_for_languages_with_commands() {
+    let mut config = Config::default();
+    config.postprocess.punctuation = PunctuationMode::Spoken;
+    config
+        .postprocess
+        .stages
+        .push(PostprocessStage::PunctuationCommands);
+    let settings = LiveSettings::from_config(&config);
+    assert!(suppress_punctuation(&settings, Some("cs")));
+    assert!(!suppress_punctuation(&settings, Some("fr")));
+    // Auto-detection may pick any language
+    assert!(!suppress_punctuation(&settings, None));
+
+    config.model.candidate_languages = vec!["english".to_string(), "sk".to_string()];
+    assert!(suppress_punctuation(
+        &LiveSettings::from_config(&config),
+        None
+    ));
+    config.model.candidate_languages.push("fr".to_string());
+    assert!(!suppress_punctuation(
+        &LiveSettings::from_config(&config),
+        None
+    ));
+
+    // Translations are English
+    config.model.task = Task::Translate;
+    assert!(suppress_punctuation(
+        &LiveSettings::from_config(&config),
+        Some("fr")
+    ));
+
+    config.postprocess.punctuation = PunctuationMode::Model;
+    assert!(!suppress_punctuation(
+        &LiveSettings::from_config(&config),
+        Some("cs")
+    ));
+}
+
+/// Half a second of silence, then `count` one-second tones each followed by
+/// 1.5 seconds of silence.
+fn tone_bursts(sample_rate: u32, count: usize) -> Vec<f32> {
+    let secs = |s: f32| (s * sample_rate as f32) as usize;
+    let mut samples = vec![0.0; secs(0.5)];
+    for _ in 0..count {
+        samples.extend((0..secs(1.0)).map(|i| {
+            0.3 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin()
+        }));
+        samples.extend(vec![0.0; secs(1.5)]);
+    }
+    samples
+}
+
+#[tokio::test]
+async fn test_pipeline_with_scripted_models() {
+    let transcriber = ScriptedTranscriber::new(["Hello there.", "General Kenobi."]);
+    let mut engine = Engine::new(Config::default()).unwrap();
+    engine.set_model_override(ModelOverride::scripted(transcriber.clone()));
+    engine.initialize(|_| {}).await.unwrap();
+    assert!(engine.is_initialized());
+
+    engine.set_audio_source(Box::new(crate::audio::ScriptedSource::from_samples(
+        &tone_bursts(48000, 2),
+        48000,
+    )));
+    let mut events = Vec::new();
+    engine
+        .run_loop(CancellationToken::new(), |event| events.push(event))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        events,
+        vec![
+            EngineEvent::Transcription(Transcript::from_text("Hello there.")),
+            EngineEvent::Transcription(Transcript::from_text("General Kenobi.")),
+        ]
+    );
+    let requests = transcriber.requests();
+    assert_eq!(requests.len(), 2);
+    // Each utterance holds about a second of tone at 16kHz
+    assert!(requests.iter().all(|request| request.samples >= 16000));
+}
+
+#[tokio::test]
+async fn test_vocabulary_prompt_for_language_set_by_name() {
+    let config = Config::parse(
+        r#"
+[model]
+language = "slovak"
+
+[vocabulary]
+prompt = "Notes."
+
+[vocabulary.languages.sk]
+prompt = "Poznámky."
+"#,
+    )
+    .unwrap();
+    let transcriber = ScriptedTranscriber::new(["Ahoj."]);
+    let mut engine = Engine::new(config).unwrap();
+    engine.set_model_override(ModelOverride::scripted(transcriber.clone()));
+    engine.initialize(|_| {}).await.unwrap();
+    engine.set_audio_source(Box::new(crate::audio::ScriptedSource::from_samples(
+        &tone_bursts(16000, 1),
+        16000,
+    )));
+    engine
+        .run_loop(CancellationToken::new(), |_| {})
+        .await
+        .unwrap();
+
+    let requests = transcriber.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].language.as_deref(), Some("sk"));
+    assert_eq!(requests[0].prompt.as_deref(), Some("Poznámky."));
+}
+
+#[tokio::test]
+async fn test_task_change_reaches_transcriber() {
+    let transcriber = ScriptedTranscriber::new(["Hello."]);
+    let mut engine = Engine::new(Config::default()).unwrap();
+    engine.set_model_override(ModelOverride::scripted(transcriber.clone()));
+    engine.initialize(|_| {}).await.unwrap();
+    // What `Controller::set_task` does to a running engine
+    engine.shared_settings().lock().unwrap().task = Task::Translate;
+    engine.set_audio_source(Box::new(crate::audio::ScriptedSource::from_samples(
+        &tone_bursts(16000, 1),
+        16000,
+    )));
+    engine
+        .run_loop(CancellationToken::new(), |_| {})
+        .await
+        .unwrap();
+
+    let requests = transcriber.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].task, Task::Translate);
+}
+
+/// Scripted audio whose stream fails once the samples run out.
+struct FailingSource {
+    inner: crate::audio::ScriptedSource,
+    failed: bool,
+}
+
+impl AudioSource for FailingSource {
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn try_recv(&mut self) -> Option<Vec<f32>> {
+        self.inner.try_recv()
+    }
+
+    fn poll_error(&mut self) -> Option<String> {
+        (self.inner.is_finished() && !std::mem::replace(&mut self.failed, true))
+            .then(|| "device unplugged".to_string())
+    }
+}
+
+/// Still transcribing when the stream fails.
+struct SlowTranscriber;
+
+impl Transcriber for SlowTranscriber {
+    fn transcribe(&mut self, _audio: &[f32], _sample_rate: u32) -> Result<Transcript> {
+        std::thread::sleep(Duration::from_millis(300));
+        Ok(Transcript::from_text("Before the failure."))
+    }
+
+    fn set_language(&mut self, _language: Option<String>) {}
+}
+
+#[tokio::test]
+async fn test_results_emitted_while_reopening_audio() {
+    let mut config = Config::default();
+    // Reopening keeps failing, so the loop stays in the backoff
+    config.audio.source = crate::config::AudioSourceKind::File;
+    config.audio.path = Some("/nonexistent/audio.wav".into());
+    let mut engine = Engine::new(config).unwrap();
+    engine.set_model_override(ModelOverride {
+        transcriber: Arc::new(|| Ok(Box::new(SlowTranscriber))),
+        ..ModelOverride::scripted(ScriptedTranscriber::new(Vec::<String>::new()))
+    });
+    engine.initialize(|_| {}).await.unwrap();
+    engine.set_audio_source(Box::new(FailingSource {
+        inner: crate::audio::ScriptedSource::from_samples(&tone_bursts(48000, 1), 48000),
+        failed: false,
+    }));
+
+    let cancel = CancellationToken::new();
+    let stop = cancel.clone();
+    let mut events = Vec::new();
+    tokio::time::timeout(
+        Duration::from_secs(5),
+        engine.run_loop(cancel, |event| {
+            if matches!(event, EngineEvent::Transcription(_)) {
+                stop.cancel();
+            }
+            events.push(event);
+        }),
+    )
+    .await
+    .expect("transcription not emitted during backoff")
+    .unwrap();
+
+    assert_eq!(
+        events,
+        vec![
+            EngineEvent::AudioError("device unplugged".to_string()),
+            EngineEvent::Transcription(Transcript::from_text("Before the failure.")),
+        ]
+    );
+}
@@ -1,8 +1,36 @@
 use crate::config::InjectionConfig;
 use anyhow::{Context, Result};
-use enigo::{Enigo, Keyboard, Settings};
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use tracing::{debug, info, warn};
 
+/// Delivers transcribed text to the user.
+pub trait TextInjector: Send {
+    /// Type `text`. Returns false when it was not typed because the
+    /// frontmost app may not receive it.
+    fn inject_text(&mut self, text: &str) -> Result<bool>;
+
+    /// Delete `count` characters before the cursor.
+    fn delete_backward(&mut self, count: usize) -> Result<()>;
+}
+
+/// Creates the injector for a listening session.
+pub type InjectorFactory =
+    Arc<dyn Fn(InjectionConfig) -> Result<Box<dyn TextInjector>> + Send + Sync>;
+
+/// Factory typing text into the focused app with [`KeystrokeInjector`].
+pub fn keystroke_injector_factory() -> InjectorFactory {
+    Arc::new(|config| Ok(Box::new(KeystrokeInjector::new(config)?)))
+}
+
+/// Factory handing out clones of `recorder`, so a test sees everything the
+/// daemon injects.
+pub fn recording_injector_factory(recorder: &RecordingInjector) -> InjectorFactory {
+    let recorder = recorder.clone();
+    Arc::new(move |_| Ok(Box::new(recorder.clone())))
+}
+
 pub struct KeystrokeInjector {
     config: InjectionConfig,
     enigo: Enigo,
@@ -17,44 +45,166 @@ impl KeystrokeInjector {
         Ok(Self { config, enigo })
     }
 
-    pub fn inject_text(&mut self, text: &str) -> Result<()> {
-        if !self.config.allowlist.is_empty() {
-            let frontmost = match vcm_platform::frontmost::current() {
-                Ok(name) if !name.is_empty() => name,
-                Ok(_) => {
-                    warn!("Frontmost app lookup returned empty name; skipping injection");
-                    return Ok(());
-                }
-                Err(e) => {
-                    warn!(error = %e, "Failed to get frontmost app; skipping injection");
-                    return Ok(());
-                }
-            };
-
-            if !self.is_allowed(&frontmost) {
-                debug!(
-                    app = %frontmost,
-                    "Skipping injection: app not in allowlist"
-                );
-                return Ok(());
+    fn is_allowed(&self, app_name: &str) -> bool {
+        let app_lower = app_name.to_lowercase();
+        self.config
+            .allowlist
+            .iter()
+            .any(|allowed| app_lower.contains(&allowed.to_lowercase()))
+    }
+
+    /// Whether the frontmost app may receive keystrokes.
+    fn target_allowed(&self) -> bool {
+        if self.config.allowlist.is_empty() {
+            return true;
+        }
+        let frontmost = match vcm_platform::frontmost::current() {
+            Ok(name) if !name.is_empty() => name,
+            Ok(_) => {
+                warn!("Frontmost app lookup returned empty name; skipping injection");
+                return false;
             }
+            Err(e) => {
+                warn!(error = %e, "Failed to get frontmost app; skipping injection");
+                return false;
+            }
+        };
+
+        if !self.is_allowed(&frontmost) {
+            debug!(
+                app = %frontmost,
+                "Skipping injection: app not in allowlist"
+            );
+            return false;
+        }
+        true
+    }
+}
+
+impl TextInjector for KeystrokeInjector {
+    fn inject_text(&mut self, text: &str) -> Result<bool> {
+        if !self.target_allowed() {
+            return Ok(false);
         }
 
         info!(text = %text, "Injecting text as keystrokes");
-        self.enigo
-            .text(text)
+        for keystroke in keystrokes(text) {
+            match keystroke {
+                Keystroke::Text(text) => self.enigo.text(text),
+                Keystroke::Key(key) => self.enigo.key(key, Direction::Click),
+            }
             .map_err(anyhow::Error::new)
             .context("inject text")?;
+        }
+
+        Ok(true)
+    }
+
+    fn delete_backward(&mut self, count: usize) -> Result<()> {
+        if !self.target_allowed() {
+            anyhow::bail!("Frontmost app is not in the injection allowlist");
+        }
+
+        info!(count = count, "Deleting text with backspace");
+        for _ in 0..count {
+            self.enigo
+                .key(Key::Backspace, Direction::Click)
+                .map_err(anyhow::Error::new)
+                .context("delete text")?;
+        }
 
         Ok(())
     }
+}
 
-    fn is_allowed(&self, app_name: &str) -> bool {
-        let app_lower = app_name.to_lowercase();
-        self.config
-            .allowlist
-            .iter()
-            .any(|allowed| app_lower.contains(&allowed.to_lowercase()))
+/// A piece of injected text: characters to type or a key to press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keystroke<'a> {
+    Text(&'a str),
+    Key(Key),
+}
+
+/// Split `text` so line breaks and tabs are pressed as Return and Tab.
+///
+/// Typed as characters they come out differently per platform; on macOS
+/// enigo prefixes each line break with a zero-width space.
+fn keystrokes(text: &str) -> Vec<Keystroke<'_>> {
+    let mut keystrokes = Vec::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find(['\n', '\t']) {
+        if pos > 0 {
+            keystrokes.push(Keystroke::Text(&rest[..pos]));
+        }
+        let key = if rest[pos..].starts_with('\n') {
+            Key::Return
+        } else {
+            Key::Tab
+        };
+        keystrokes.push(Keystroke::Key(key));
+        rest = &rest[pos + 1..];
+    }
+    if !rest.is_empty() {
+        keystrokes.push(Keystroke::Text(rest));
+    }
+    keystrokes
+}
+
+/// Injector that records text instead of typing it, for tests.
+///
+/// Clones share the record.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingInjector {
+    texts: Arc<Mutex<Vec<String>>>,
+    deletions: Arc<Mutex<Vec<usize>>>,
+    declining: Arc<AtomicBool>,
+}
+
+impl RecordingInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Text injected so far, one entry per call.
+    pub fn texts(&self) -> Vec<String> {
+        self.texts
+            .lock()
+            .map(|texts| texts.clone())
+            .unwrap_or_default()
+    }
+
+    /// Refuse text from now on without recording it, as the keystroke
+    /// injector does for apps outside the allowlist.
+    pub fn set_declining(&self, declining: bool) {
+        self.declining.store(declining, Ordering::Relaxed);
+    }
+
+    /// Characters deleted so far, one entry per call.
+    pub fn deletions(&self) -> Vec<usize> {
+        self.deletions
+            .lock()
+            .map(|deletions| deletions.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl TextInjector for RecordingInjector {
+    fn inject_text(&mut self, text: &str) -> Result<bool> {
+        if self.declining.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        self.texts
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to lock injected text: {e}"))?
+            .push(text.to_string());
+        Ok(true)
+    }
+
+    fn delete_backward(&mut self, count: usize) -> Result<()> {
+        self.deletions
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to lock deletions: {e}"))?
+            .push(count);
+        Ok(())
     }
 }
 
@@ -13,6 +13,7 @@ fn test_is_allowed_empty_allowlist() {
 fn test_is_allowed_case_insensitive() {
     let config = InjectionConfig {
         allowlist: vec!["Terminal".to_string(), "VSCode".to_string()],
+        ..InjectionConfig::default()
     };
     let injector = KeystrokeInjector::new(config).expect("should create injector");
 
@@ -28,6 +29,7 @@ fn test_is_allowed_case_insensitive() {
 fn test_is_allowed_partial_match() {
     let config = InjectionConfig {
         allowlist: vec!["Code".to_string()],
+        ..InjectionConfig::default()
     };
     let injector = KeystrokeInjector::new(config).expect("should create injector");
 
@@ -44,3 +46,29 @@ fn test_get_frontmost_app() {
     let app = vcm_platform::frontmost::current().expect("frontmost lookup should succeed");
     assert!(!app.is_empty(), "frontmost app name should not be empty");
 }
+
+#[test]
+fn test_recording_injector_shares_record() {
+    let recorder = RecordingInjector::new();
+    let mut injector: Box<dyn TextInjector> = Box::new(recorder.clone());
+    injector.inject_text("hello ").unwrap();
+    injector.inject_text("world").unwrap();
+    assert_eq!(recorder.texts(), vec!["hello ", "world"]);
+}
+
+#[test]
+fn test_keystrokes_press_line_breaks_and_tabs() {
+    assert_eq!(
+        keystrokes("Dear Anna,\n\nname\tvalue"),
+        vec![
+            Keystroke::Text("Dear Anna,"),
+            Keystroke::Key(Key::Return),
+            Keystroke::Key(Key::Return),
+            Keystroke::Text("name"),
+            Keystroke::Key(Key::Tab),
+            Keystroke::Text("value"),
+        ]
+    );
+    assert_eq!(keystrokes("plain"), vec![Keystroke::Text("plain")]);
+    assert!(keystrokes("").is_empty());
+}
@@ -6,9 +6,13 @@ pub mod dirs;
 pub mod engine;
 pub mod inject;
 pub mod models;
+pub mod postprocess;
+pub mod segment;
 pub mod server;
 pub mod socket;
+pub mod streaming;
 pub mod transcribe;
+pub mod typist;
 pub mod vad;
 
 pub async fn run() -> anyhow::Result<()> {
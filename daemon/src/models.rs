@@ -208,6 +208,7 @@ struct CoreMlModelInfo {
 }
 
 /// Manages model downloads and storage.
+#[derive(Debug, Clone)]
 pub struct ModelManager {
     models_dir: PathBuf,
 }
@@ -271,6 +272,17 @@ impl ModelManager {
     ///
     /// Returns the path to the model file.
     pub async fn ensure_model(&self, model: ModelId) -> Result<PathBuf> {
+        self.ensure_model_with_progress(model, &|_, _| {}).await
+    }
+
+    /// Like [`ensure_model`](Self::ensure_model), calling `on_progress` with
+    /// the bytes downloaded so far and the total size (0 if unknown) for
+    /// every chunk received.
+    pub async fn ensure_model_with_progress(
+        &self,
+        model: ModelId,
+        on_progress: &(dyn Fn(u64, u64) + Send + Sync),
+    ) -> Result<PathBuf> {
         let info = model.info();
         let model_path = self.models_dir.join(info.filename);
 
@@ -307,7 +319,7 @@ impl ModelManager {
 
         if needs_download {
             // Download the model
-            self.download_model(&info, &model_path).await?;
+            self.download_model(&info, &model_path, on_progress).await?;
         }
 
         // Ensure CoreML encoder is available (macOS only)
@@ -382,7 +394,12 @@ impl ModelManager {
     }
 
     /// Download a model from its URL with progress bar and resume support.
-    async fn download_model(&self, info: &ModelInfo, dest: &Path) -> Result<()> {
+    async fn download_model(
+        &self,
+        info: &ModelInfo,
+        dest: &Path,
+        on_progress: &(dyn Fn(u64, u64) + Send + Sync),
+    ) -> Result<()> {
         if let Some(parent) = dest.parent() {
             fs::create_dir_all(parent)
                 .await
@@ -407,7 +424,7 @@ impl ModelManager {
         if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
             warn!("Server rejected range request (416), restarting download from scratch");
             let _ = fs::remove_file(&temp_path).await;
-            return Box::pin(self.download_model(info, dest)).await;
+            return Box::pin(self.download_model(info, dest, on_progress)).await;
         }
 
         if !status.is_success() && status != reqwest::StatusCode::PARTIAL_CONTENT {
@@ -425,8 +442,15 @@ impl ModelManager {
         }
 
         let downloaded_start = if is_resume { existing_size } else { 0 };
-        let downloaded =
-            stream_to_file(response, &temp_path, info, is_resume, downloaded_start).await?;
+        let downloaded = stream_to_file(
+            response,
+            &temp_path,
+            info,
+            is_resume,
+            downloaded_start,
+            on_progress,
+        )
+        .await?;
 
         validate_and_finalize(downloaded, info, &temp_path, dest).await
     }
@@ -555,6 +579,7 @@ async fn stream_to_file(
     info: &ModelInfo,
     is_resume: bool,
     downloaded_start: u64,
+    on_progress: &(dyn Fn(u64, u64) + Send + Sync),
 ) -> Result<u64> {
     let total_size = info.size_bytes.unwrap_or(0);
 
@@ -587,6 +612,7 @@ async fn stream_to_file(
             .context("Failed to write chunk")?;
         downloaded += chunk.len() as u64;
         pb.set_position(downloaded);
+        on_progress(downloaded, total_size);
     }
 
     file.sync_all().await.context("Failed to sync model file")?;
@@ -0,0 +1,198 @@
+//! Spoken punctuation, formatting and editing commands by language.
+
+use super::words;
+
+/// How a command's symbol joins the words around it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Spacing {
+    /// Follows the previous word and replaces punctuation the model put
+    /// there, e.g. a comma.
+    Closing,
+    /// Follows the previous word, e.g. a closing quote.
+    CloseBracket,
+    /// Precedes the next word, e.g. an opening quote.
+    Opening,
+    /// Joins the words on either side, e.g. a hyphen.
+    Joining,
+    /// Stands between spaces, e.g. a dash.
+    Spaced,
+    /// Ends the line; the next word starts the new one.
+    Break,
+}
+
+/// A spoken command and what it types.
+pub(super) struct Command {
+    pub phrase: &'static str,
+    pub symbol: &'static str,
+    pub spacing: Spacing,
+}
+
+/// Spoken commands of one language.
+pub(super) struct Grammar {
+    /// ISO 639-1 code.
+    pub language: &'static str,
+    pub commands: &'static [Command],
+    /// Word said before a command while the model punctuates, so ordinary
+    /// words like "period" stay words.
+    pub prefix: &'static str,
+    /// Utterances that undo the previous one.
+    pub undo: &'static [&'static str],
+    /// Words are lowercase unless they start a sentence, so a capital the
+    /// model put after its own period can be undone. Not so in German,
+    /// where nouns are capitalized.
+    pub sentence_case: bool,
+}
+
+const fn command(phrase: &'static str, symbol: &'static str, spacing: Spacing) -> Command {
+    Command {
+        phrase,
+        symbol,
+        spacing,
+    }
+}
+
+use Spacing::{Break, CloseBracket, Closing, Joining, Opening, Spaced};
+
+const ENGLISH: &[Command] = &[
+    command("comma", ",", Closing),
+    command("period", ".", Closing),
+    command("full stop", ".", Closing),
+    command("question mark", "?", Closing),
+    command("exclamation mark", "!", Closing),
+    command("exclamation point", "!", Closing),
+    command("colon", ":", Closing),
+    command("semicolon", ";", Closing),
+    command("ellipsis", "…", Closing),
+    command("open quote", "\"", Opening),
+    command("begin quote", "\"", Opening),
+    command("close quote", "\"", CloseBracket),
+    command("end quote", "\"", CloseBracket),
+    command("unquote", "\"", CloseBracket),
+    command("open paren", "(", Opening),
+    command("open parenthesis", "(", Opening),
+    command("close paren", ")", CloseBracket),
+    command("close parenthesis", ")", CloseBracket),
+    command("hyphen", "-", Joining),
+    command("dash", "–", Spaced),
+    command("new line", "\n", Break),
+    command("new paragraph", "\n\n", Break),
+    command("tab key", "\t", Break),
+];
+
+const CZECH: &[Command] = &[
+    command("čárka", ",", Closing),
+    command("tečka", ".", Closing),
+    command("otazník", "?", Closing),
+    command("vykřičník", "!", Closing),
+    command("dvojtečka", ":", Closing),
+    command("středník", ";", Closing),
+    command("tři tečky", "…", Closing),
+    command("otevřít uvozovky", "„", Opening),
+    command("zavřít uvozovky", "“", CloseBracket),
+    command("levá závorka", "(", Opening),
+    command("pravá závorka", ")", CloseBracket),
+    command("spojovník", "-", Joining),
+    command("pomlčka", "–", Spaced),
+    command("nový řádek", "\n", Break),
+    command("nová řádka", "\n", Break),
+    command("nový odstavec", "\n\n", Break),
+    command("tabulátor", "\t", Break),
+];
+
+const SLOVAK: &[Command] = &[
+    command("čiarka", ",", Closing),
+    command("bodka", ".", Closing),
+    command("otáznik", "?", Closing),
+    command("výkričník", "!", Closing),
+    command("dvojbodka", ":", Closing),
+    command("bodkočiarka", ";", Closing),
+    command("tri bodky", "…", Closing),
+    command("otvoriť úvodzovky", "„", Opening),
+    command("zatvoriť úvodzovky", "“", CloseBracket),
+    command("ľavá zátvorka", "(", Opening),
+    command("pravá zátvorka", ")", CloseBracket),
+    command("spojovník", "-", Joining),
+    command("pomlčka", "–", Spaced),
+    command("nový riadok", "\n", Break),
+    command("nový odsek", "\n\n", Break),
+    command("tabulátor", "\t", Break),
+];
+
+const GERMAN: &[Command] = &[
+    command("komma", ",", Closing),
+    command("punkt", ".", Closing),
+    command("fragezeichen", "?", Closing),
+    command("ausrufezeichen", "!", Closing),
+    command("doppelpunkt", ":", Closing),
+    command("semikolon", ";", Closing),
+    command("anführungszeichen auf", "„", Opening),
+    command("anführungszeichen zu", "“", CloseBracket),
+    command("klammer auf", "(", Opening),
+    command("klammer zu", ")", CloseBracket),
+    command("bindestrich", "-", Joining),
+    command("gedankenstrich", "–", Spaced),
+    command("neue zeile", "\n", Break),
+    command("neuer absatz", "\n\n", Break),
+    command("tabulator", "\t", Break),
+];
+
+/// Code of the grammar for text in `language`, given as a code or name.
+/// Text of unknown language is treated as English.
+pub(super) fn grammar_language(language: Option<&str>) -> Option<&'static str> {
+    let code = language.map_or(Some("en"), crate::transcribe::language_code)?;
+    GRAMMARS
+        .iter()
+        .map(|grammar| grammar.language)
+        .find(|&language| language == code)
+}
+
+/// Whether text in `language` has spoken commands.
+pub fn has_spoken_commands(language: Option<&str>) -> bool {
+    grammar_language(language).is_some()
+}
+
+/// Whether `text`, spoken in `language`, asks to undo the previous
+/// utterance, e.g. "scratch that". Text of unknown language is matched
+/// against the English commands.
+pub fn is_undo_command(text: &str, language: Option<&str>) -> bool {
+    let language = grammar_language(language);
+    let spoken = words::phrase(text);
+    !spoken.is_empty()
+        && GRAMMARS
+            .iter()
+            .filter(|grammar| Some(grammar.language) == language)
+            .flat_map(|grammar| grammar.undo)
+            .any(|command| words::phrase(command) == spoken)
+}
+
+/// Languages with spoken commands.
+pub(super) const GRAMMARS: &[Grammar] = &[
+    Grammar {
+        language: "en",
+        commands: ENGLISH,
+        prefix: "insert",
+        undo: &["scratch that", "undo that"],
+        sentence_case: true,
+    },
+    Grammar {
+        language: "cs",
+        commands: CZECH,
+        prefix: "vlož",
+        undo: &["smaž to", "vrať to"],
+        sentence_case: true,
+    },
+    Grammar {
+        language: "sk",
+        commands: SLOVAK,
+        prefix: "vlož",
+        undo: &["vymaž to", "vráť to"],
+        sentence_case: true,
+    },
+    Grammar {
+        language: "de",
+        commands: GERMAN,
+        prefix: "setze",
+        undo: &["streich das", "lösch das"],
+        sentence_case: false,
+    },
+];
@@ -0,0 +1,91 @@
+//! Clean-up of transcribed text before it is typed.
+//!
+//! Transcribers return text as they heard it. A [`TextPipeline`] runs the
+//! stages listed in `[postprocess]` over it in order, so spoken punctuation,
+//! filler words and recurring mishearings can be fixed before injection.
+
+mod commands;
+mod stages;
+mod words;
+
+pub use commands::{has_spoken_commands, is_undo_command};
+pub use stages::{Capitalization, FilterWords, PunctuationCommands, Replacements, Whitespace};
+pub use words::capitalize;
+
+use crate::config::{PostprocessConfig, PostprocessStage};
+use crate::transcribe::Transcript;
+use tracing::trace;
+
+/// Punctuation ending a sentence.
+pub const SENTENCE_END: &[char] = &['.', '!', '?', '…'];
+
+/// One step of text post-processing.
+pub trait TextProcessor: Send + Sync {
+    /// Stage name for logs.
+    fn name(&self) -> &'static str;
+
+    /// Rewrite `text`, spoken in `language` (ISO 639-1) if known.
+    fn process(&self, text: &str, language: Option<&str>) -> String;
+}
+
+/// Text processors run one after another.
+#[derive(Default)]
+pub struct TextPipeline {
+    stages: Vec<Box<dyn TextProcessor>>,
+}
+
+impl TextPipeline {
+    /// Build the stages listed in `config`, in order.
+    pub fn new(config: &PostprocessConfig) -> Self {
+        let stages = config
+            .stages
+            .iter()
+            .map(|stage| -> Box<dyn TextProcessor> {
+                match stage {
+                    PostprocessStage::Whitespace => Box::new(Whitespace),
+                    PostprocessStage::Capitalization => Box::new(Capitalization),
+                    PostprocessStage::Replacements => {
+                        Box::new(Replacements::new(&config.replacements))
+                    }
+                    PostprocessStage::PunctuationCommands => {
+                        Box::new(PunctuationCommands::new(config.punctuation))
+                    }
+                    PostprocessStage::FilterWords => {
+                        Box::new(FilterWords::new(&config.filter_words))
+                    }
+                }
+            })
+            .collect();
+        Self { stages }
+    }
+
+    /// A pipeline of the given stages.
+    pub fn from_stages(stages: Vec<Box<dyn TextProcessor>>) -> Self {
+        Self { stages }
+    }
+
+    /// Run every stage over `text`.
+    pub fn process(&self, text: &str, language: Option<&str>) -> String {
+        self.stages.iter().fold(text.to_string(), |text, stage| {
+            let processed = stage.process(&text, language);
+            if processed != text {
+                trace!(stage = stage.name(), text = %processed, "Text post-processed");
+            }
+            processed
+        })
+    }
+
+    /// Process the text of `transcript` in the language of the text, keeping
+    /// the original in `raw_text` when it changed. Segments keep the text as
+    /// transcribed.
+    pub fn apply(&self, transcript: &mut Transcript) {
+        let processed = self.process(&transcript.text, transcript.text_language());
+        if processed != transcript.text {
+            transcript.raw_text = Some(std::mem::replace(&mut transcript.text, processed));
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "mod_test.rs"]
+mod tests;
@@ -0,0 +1,110 @@
+use super::*;
+use crate::config::PunctuationMode;
+use std::collections::BTreeMap;
+
+fn config(stages: Vec<PostprocessStage>) -> PostprocessConfig {
+    PostprocessConfig {
+        stages,
+        replacements: BTreeMap::from([("vee cee em".to_string(), "VCM".to_string())]),
+        filter_words: vec!["um".to_string()],
+        punctuation: PunctuationMode::Model,
+    }
+}
+
+#[test]
+fn test_stages_run_in_order() {
+    let pipeline = TextPipeline::new(&config(vec![
+        PostprocessStage::FilterWords,
+        PostprocessStage::Replacements,
+        PostprocessStage::PunctuationCommands,
+        PostprocessStage::Whitespace,
+        PostprocessStage::Capitalization,
+    ]));
+    assert_eq!(
+        pipeline.process(" um  vee cee em works insert period it types  ", Some("en")),
+        "VCM works. It types"
+    );
+}
+
+#[test]
+fn test_order_matters() {
+    // Capitalizing first leaves a lowercase word after the spoken period
+    let pipeline = TextPipeline::new(&config(vec![
+        PostprocessStage::Capitalization,
+        PostprocessStage::PunctuationCommands,
+    ]));
+    assert_eq!(
+        pipeline.process("done insert period next", None),
+        "Done. next"
+    );
+}
+
+#[test]
+fn test_default_pipeline_keeps_plain_text() {
+    let pipeline = TextPipeline::new(&PostprocessConfig::default());
+    assert_eq!(pipeline.process("Hello there.", None), "Hello there.");
+}
+
+#[test]
+fn test_apply_keeps_raw_text_when_changed() {
+    let pipeline = TextPipeline::new(&config(vec![PostprocessStage::PunctuationCommands]));
+
+    let mut transcript = Transcript {
+        language: Some("en".to_string()),
+        ..Transcript::from_text("yes insert comma please")
+    };
+    pipeline.apply(&mut transcript);
+    assert_eq!(transcript.text, "yes, please");
+    assert_eq!(
+        transcript.raw_text.as_deref(),
+        Some("yes insert comma please")
+    );
+
+    let mut unchanged = Transcript::from_text("no thanks");
+    pipeline.apply(&mut unchanged);
+    assert_eq!(unchanged.text, "no thanks");
+    assert_eq!(unchanged.raw_text, None);
+}
+
+#[test]
+fn test_apply_uses_english_for_translations() {
+    let pipeline = TextPipeline::new(&config(vec![PostprocessStage::PunctuationCommands]));
+
+    let mut transcript = Transcript {
+        language: Some("cs".to_string()),
+        translated: true,
+        ..Transcript::from_text("yes insert comma please")
+    };
+    pipeline.apply(&mut transcript);
+    assert_eq!(transcript.text, "yes, please");
+    assert_eq!(transcript.language.as_deref(), Some("cs"));
+}
+
+struct Shout;
+
+impl TextProcessor for Shout {
+    fn name(&self) -> &'static str {
+        "shout"
+    }
+
+    fn process(&self, text: &str, _language: Option<&str>) -> String {
+        text.to_uppercase()
+    }
+}
+
+#[test]
+fn test_custom_stages() {
+    let pipeline = TextPipeline::from_stages(vec![Box::new(Whitespace), Box::new(Shout)]);
+    assert_eq!(pipeline.process(" hi  there ", None), "HI THERE");
+}
+
+#[test]
+fn test_undo_commands_per_language() {
+    assert!(is_undo_command("Scratch that.", Some("en")));
+    assert!(is_undo_command("undo that", None));
+    assert!(is_undo_command("Smaž to!", Some("cs")));
+    assert!(is_undo_command("Smaž to!", Some("czech")));
+    assert!(!is_undo_command("scratch that", Some("cs")));
+    assert!(!is_undo_command("Scratch that idea.", Some("en")));
+    assert!(!is_undo_command("", Some("en")));
+}
@@ -0,0 +1,349 @@
+//! Built-in post-processing stages.
+
+use super::commands::{GRAMMARS, Spacing, grammar_language};
+use super::words::{self, Word};
+use super::{SENTENCE_END, TextProcessor};
+use crate::config::PunctuationMode;
+use std::collections::{BTreeMap, HashMap};
+
+/// Marks ending a clause or sentence.
+const PUNCTUATION: &[char] = &[',', '.', '!', '?', ':', ';', '…'];
+
+/// Punctuation that follows a word without a space.
+const CLOSING: &[char] = &[',', '.', '!', '?', ':', ';', '…', ')', ']', '}'];
+
+/// Collapses runs of spaces, trims lines and removes spaces before
+/// punctuation. Line breaks are kept.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Whitespace;
+
+impl TextProcessor for Whitespace {
+    fn name(&self) -> &'static str {
+        "whitespace"
+    }
+
+    fn process(&self, text: &str, _language: Option<&str>) -> String {
+        let lines: Vec<String> = text
+            .split('\n')
+            .map(|line| {
+                let mut out = String::with_capacity(line.len());
+                for word in line.split_whitespace() {
+                    if !out.is_empty() && !word.starts_with(CLOSING) {
+                        out.push(' ');
+                    }
+                    out.push_str(word);
+                }
+                out
+            })
+            .collect();
+        lines.join("\n")
+    }
+}
+
+/// Capitalizes the first letter of the text, of each sentence and of each
+/// line.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Capitalization;
+
+impl TextProcessor for Capitalization {
+    fn name(&self) -> &'static str {
+        "capitalization"
+    }
+
+    fn process(&self, text: &str, _language: Option<&str>) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut capitalize = true;
+        let mut sentence_ended = false;
+        for c in text.chars() {
+            if c == '\n' || (c.is_whitespace() && sentence_ended) {
+                capitalize = true;
+            } else if SENTENCE_END.contains(&c) {
+                sentence_ended = true;
+            } else if c.is_alphanumeric() {
+                sentence_ended = false;
+                if std::mem::take(&mut capitalize) {
+                    out.extend(c.to_uppercase());
+                    continue;
+                }
+            } else if !c.is_whitespace() {
+                // Closing quotes may follow the end of a sentence; other
+                // marks mean it went on ("etc., and")
+                sentence_ended &= matches!(c, '"' | '\'' | ')' | ']' | '”' | '’');
+            }
+            out.push(c);
+        }
+        out
+    }
+}
+
+/// Replaces phrases with configured text, e.g. to fix names the model
+/// keeps mishearing. Matches whole words regardless of case and keeps
+/// punctuation around the phrase.
+#[derive(Debug, Clone, Default)]
+pub struct Replacements {
+    phrases: Vec<(Vec<String>, String)>,
+}
+
+impl Replacements {
+    pub fn new(replacements: &BTreeMap<String, String>) -> Self {
+        let mut phrases: Vec<_> = replacements
+            .iter()
+            .map(|(phrase, replacement)| (words::phrase(phrase), replacement.clone()))
+            .filter(|(phrase, _)| !phrase.is_empty())
+            .collect();
+        words::sort_phrases(&mut phrases);
+        Self { phrases }
+    }
+}
+
+impl TextProcessor for Replacements {
+    fn name(&self) -> &'static str {
+        "replacements"
+    }
+
+    fn process(&self, text: &str, _language: Option<&str>) -> String {
+        let words = words::split(text);
+        let mut out = String::with_capacity(text.len());
+        let mut i = 0;
+        while i < words.len() {
+            if let Some((len, replacement)) = words::longest_match(&words[i..], &self.phrases) {
+                out.push_str(words[i].space);
+                out.push_str(words[i].lead);
+                out.push_str(replacement);
+                out.push_str(words[i + len - 1].trail);
+                i += len;
+            } else {
+                out.push_str(words[i].space);
+                words[i].push_to(&mut out);
+                i += 1;
+            }
+        }
+        out
+    }
+}
+
+/// Punctuation the model adds, removed in [`PunctuationMode::Spoken`].
+const MODEL_PUNCTUATION: &[char] = &[
+    ',', '.', '!', '?', ':', ';', '…', '"', '“', '”', '„', '(', ')', '-', '–', '—',
+];
+
+/// Spoken commands of one language, ready for matching.
+#[derive(Debug, Clone)]
+struct Language {
+    commands: Vec<(Vec<String>, (&'static str, Spacing))>,
+    sentence_case: bool,
+}
+
+/// Turns spoken punctuation and formatting such as "comma", "open quote"
+/// or "new line" into symbols, in the language of the text.
+///
+/// Text in a language without spoken commands is left alone; text of
+/// unknown language is treated as English. While the model punctuates,
+/// commands need the language's prefix ("insert comma"), since the bare
+/// words are ordinary dictation too ("a trial period").
+#[derive(Debug, Clone)]
+pub struct PunctuationCommands {
+    mode: PunctuationMode,
+    /// Commands by language code.
+    languages: HashMap<&'static str, Language>,
+}
+
+impl PunctuationCommands {
+    pub fn new(mode: PunctuationMode) -> Self {
+        let languages = GRAMMARS
+            .iter()
+            .map(|grammar| {
+                let mut commands: Vec<_> = grammar
+                    .commands
+                    .iter()
+                    .map(|command| {
+                        let phrase = match mode {
+                            PunctuationMode::Spoken => words::phrase(command.phrase),
+                            PunctuationMode::Model => {
+                                words::phrase(&format!("{} {}", grammar.prefix, command.phrase))
+                            }
+                        };
+                        (phrase, (command.symbol, command.spacing))
+                    })
+                    .collect();
+                words::sort_phrases(&mut commands);
+                let language = Language {
+                    commands,
+                    sentence_case: grammar.sentence_case,
+                };
+                (grammar.language, language)
+            })
+            .collect();
+        Self { mode, languages }
+    }
+}
+
+impl Default for PunctuationCommands {
+    fn default() -> Self {
+        Self::new(PunctuationMode::default())
+    }
+}
+
+impl TextProcessor for PunctuationCommands {
+    fn name(&self) -> &'static str {
+        "punctuation_commands"
+    }
+
+    fn process(&self, text: &str, language: Option<&str>) -> String {
+        let Some(grammar) = grammar_language(language).and_then(|code| self.languages.get(code))
+        else {
+            return text.to_string();
+        };
+        let spoken = self.mode == PunctuationMode::Spoken;
+        let words = words::split(text);
+        let mut out = String::with_capacity(text.len());
+        // The next word follows the last symbol without a space
+        let mut glue = false;
+        // The model ended a sentence before the next word
+        let mut model_sentence_end = false;
+        let mut i = 0;
+        while i < words.len() {
+            let word = &words[i];
+            if let Some((len, &(symbol, spacing))) =
+                words::longest_match(&words[i..], &grammar.commands)
+            {
+                glue = push_command(&mut out, word.space, symbol, spacing, glue);
+                model_sentence_end = false;
+                i += len;
+                continue;
+            }
+            i += 1;
+
+            if !spoken {
+                if !glue {
+                    out.push_str(word.space);
+                }
+                word.push_to(&mut out);
+                glue = false;
+                continue;
+            }
+
+            let lead: String = word
+                .lead
+                .chars()
+                .filter(|c| !MODEL_PUNCTUATION.contains(c))
+                .collect();
+            let trail: String = word
+                .trail
+                .chars()
+                .filter(|c| !MODEL_PUNCTUATION.contains(c))
+                .collect();
+            if lead.is_empty() && word.core.is_empty() && trail.is_empty() {
+                continue;
+            }
+            if !glue {
+                out.push_str(word.space);
+            }
+            out.push_str(&lead);
+            if model_sentence_end && grammar.sentence_case {
+                out.push_str(&words::uncapitalize(word.core));
+            } else {
+                out.push_str(word.core);
+            }
+            out.push_str(&trail);
+            glue = false;
+            model_sentence_end = word.trail.contains(SENTENCE_END);
+        }
+        out
+    }
+}
+
+/// Append a command's `symbol` to `out`, where `space` was spoken before
+/// the command. Returns whether the next word attaches to the symbol.
+fn push_command(out: &mut String, space: &str, symbol: &str, spacing: Spacing, glue: bool) -> bool {
+    let trim_spaces = |out: &mut String| out.truncate(out.trim_end_matches(' ').len());
+    match spacing {
+        Spacing::Closing => {
+            // The model often punctuates around the spoken command itself
+            // ("Hello, comma, world"); the command wins
+            let kept = out
+                .trim_end_matches(|c: char| c == ' ' || PUNCTUATION.contains(&c))
+                .len();
+            out.truncate(kept);
+            out.push_str(symbol);
+            false
+        }
+        Spacing::CloseBracket => {
+            trim_spaces(out);
+            out.push_str(symbol);
+            false
+        }
+        Spacing::Opening | Spacing::Spaced => {
+            if !glue {
+                out.push_str(space);
+            }
+            out.push_str(symbol);
+            spacing == Spacing::Opening
+        }
+        Spacing::Joining | Spacing::Break => {
+            trim_spaces(out);
+            out.push_str(symbol);
+            true
+        }
+    }
+}
+
+/// Removes configured words and phrases, e.g. fillers like "um".
+///
+/// Sentence-ending punctuation after a removed phrase moves to the word
+/// before it.
+#[derive(Debug, Clone, Default)]
+pub struct FilterWords {
+    phrases: Vec<(Vec<String>, ())>,
+}
+
+impl FilterWords {
+    pub fn new(filter_words: &[String]) -> Self {
+        let mut phrases: Vec<_> = filter_words
+            .iter()
+            .map(|phrase| (words::phrase(phrase), ()))
+            .filter(|(phrase, _)| !phrase.is_empty())
+            .collect();
+        words::sort_phrases(&mut phrases);
+        Self { phrases }
+    }
+}
+
+impl TextProcessor for FilterWords {
+    fn name(&self) -> &'static str {
+        "filter_words"
+    }
+
+    fn process(&self, text: &str, _language: Option<&str>) -> String {
+        let words = words::split(text);
+        let mut out = String::with_capacity(text.len());
+        let mut i = 0;
+        while i < words.len() {
+            if let Some((len, ())) = words::longest_match(&words[i..], &self.phrases) {
+                let removed: &Word = &words[i + len - 1];
+                if let Some(end) = removed.trail.chars().find(|c| SENTENCE_END.contains(c))
+                    && !out.is_empty()
+                {
+                    let kept = out.trim_end_matches([',', ';', ':']).len();
+                    out.truncate(kept);
+                    if !out.ends_with(SENTENCE_END) {
+                        out.push(end);
+                    }
+                }
+                i += len;
+                continue;
+            }
+            // Nothing is left before this word if everything was removed
+            if !(out.is_empty() && i > 0) {
+                out.push_str(words[i].space);
+            }
+            words[i].push_to(&mut out);
+            i += 1;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+#[path = "stages_test.rs"]
+mod tests;
@@ -0,0 +1,213 @@
+use super::*;
+
+fn replacements(pairs: &[(&str, &str)]) -> Replacements {
+    Replacements::new(
+        &pairs
+            .iter()
+            .map(|(phrase, replacement)| (phrase.to_string(), replacement.to_string()))
+            .collect(),
+    )
+}
+
+fn filter_words(phrases: &[&str]) -> FilterWords {
+    FilterWords::new(&phrases.iter().map(|p| p.to_string()).collect::<Vec<_>>())
+}
+
+#[test]
+fn test_whitespace_collapsed_and_trimmed() {
+    assert_eq!(
+        Whitespace.process("  Hello   world , how are you ?  ", None),
+        "Hello world, how are you?"
+    );
+}
+
+#[test]
+fn test_whitespace_keeps_line_breaks() {
+    assert_eq!(
+        Whitespace.process("Dear Anna, \n\n  thanks .", None),
+        "Dear Anna,\n\nthanks."
+    );
+}
+
+#[test]
+fn test_capitalization_of_sentences_and_lines() {
+    assert_eq!(
+        Capitalization.process("hello. how are you? fine\nthanks", None),
+        "Hello. How are you? Fine\nThanks"
+    );
+}
+
+#[test]
+fn test_capitalization_skips_numbers_and_abbreviations() {
+    assert_eq!(
+        Capitalization.process("it costs 3.50 dollars, etc., and more", None),
+        "It costs 3.50 dollars, etc., and more"
+    );
+    assert_eq!(
+        Capitalization.process("\"quoted.\" next", None),
+        "\"Quoted.\" Next"
+    );
+}
+
+#[test]
+fn test_replacements_match_whole_words_ignoring_case() {
+    let stage = replacements(&[("gee mail", "Gmail"), ("vcm", "VCM")]);
+    assert_eq!(
+        stage.process("Check Gee Mail, then vcm. Not vcmctl.", None),
+        "Check Gmail, then VCM. Not vcmctl."
+    );
+}
+
+#[test]
+fn test_replacements_prefer_longest_phrase() {
+    let stage = replacements(&[("new", "old"), ("new york", "NYC")]);
+    assert_eq!(stage.process("new york is new", None), "NYC is old");
+}
+
+#[test]
+fn test_punctuation_commands() {
+    let stage = PunctuationCommands::default();
+    assert_eq!(
+        stage.process(
+            "hello insert comma how are you insert question mark",
+            Some("en")
+        ),
+        "hello, how are you?"
+    );
+    assert_eq!(
+        stage.process(
+            "Dear Anna insert new paragraph thanks insert full stop",
+            None
+        ),
+        "Dear Anna\n\nthanks."
+    );
+}
+
+#[test]
+fn test_punctuation_commands_replace_model_punctuation() {
+    let stage = PunctuationCommands::default();
+    assert_eq!(
+        stage.process("Hello, insert comma, world. Insert period.", Some("en")),
+        "Hello, world."
+    );
+    assert_eq!(
+        stage.process("First line. Insert new line. Second line.", Some("en")),
+        "First line.\nSecond line."
+    );
+}
+
+#[test]
+fn test_punctuation_commands_only_for_known_languages() {
+    let stage = PunctuationCommands::new(PunctuationMode::Spoken);
+    assert_eq!(stage.process("bonjour comma", Some("fr")), "bonjour comma");
+    // Commands of other languages are not matched
+    assert_eq!(stage.process("ahoj comma", Some("cs")), "ahoj comma");
+}
+
+#[test]
+fn test_bare_command_words_kept_while_model_punctuates() {
+    let stage = PunctuationCommands::default();
+    assert_eq!(
+        stage.process(
+            "It was a trial period, then a new line of work.",
+            Some("en")
+        ),
+        "It was a trial period, then a new line of work."
+    );
+    assert_eq!(stage.process("Ich setze Punkt", Some("de")), "Ich.");
+}
+
+#[test]
+fn test_punctuation_commands_per_language() {
+    let stage = PunctuationCommands::new(PunctuationMode::Spoken);
+    assert_eq!(
+        stage.process("ahoj čárka jak se máš otazník", Some("cs")),
+        "ahoj, jak se máš?"
+    );
+    assert_eq!(
+        stage.process(
+            "Hallo Komma neue Zeile Klammer auf Test Klammer zu",
+            Some("de")
+        ),
+        "Hallo,\n(Test)"
+    );
+}
+
+#[test]
+fn test_quotes_brackets_and_dashes() {
+    let stage = PunctuationCommands::new(PunctuationMode::Spoken);
+    assert_eq!(
+        stage.process(
+            "she said open quote well hyphen known close quote dash twice",
+            Some("en"),
+        ),
+        "she said \"well-known\" – twice"
+    );
+    assert_eq!(
+        stage.process("name tab key value", Some("en")),
+        "name\tvalue"
+    );
+}
+
+#[test]
+fn test_spoken_mode_drops_model_punctuation() {
+    let stage = PunctuationCommands::new(PunctuationMode::Spoken);
+    assert_eq!(
+        stage.process(
+            "Hello, world. How are you? I'm fine comma thanks",
+            Some("en")
+        ),
+        "Hello world how are you I'm fine, thanks"
+    );
+    // Names with more capitals and German nouns keep their case
+    assert_eq!(
+        stage.process("Done. McDonald called.", Some("en")),
+        "Done McDonald called"
+    );
+    assert_eq!(
+        stage.process("Ich gehe. Haus Punkt", Some("de")),
+        "Ich gehe Haus."
+    );
+}
+
+#[test]
+fn test_spoken_mode_keeps_punctuation_inside_words() {
+    let stage = PunctuationCommands::new(PunctuationMode::Spoken);
+    assert_eq!(
+        stage.process("It's 3.5 e-mails - really.", Some("en")),
+        "It's 3.5 e-mails really"
+    );
+}
+
+#[test]
+fn test_filter_words_removed() {
+    let stage = filter_words(&["um", "you know"]);
+    assert_eq!(
+        stage.process("Um, so I was, you know, thinking", None),
+        "so I was, thinking"
+    );
+}
+
+#[test]
+fn test_filter_words_keep_sentence_end() {
+    let stage = filter_words(&["um"]);
+    assert_eq!(stage.process("That is it, um.", None), "That is it.");
+    assert_eq!(stage.process("Um.", None), "");
+}
+
+#[test]
+fn test_stages_keep_text_without_matches() {
+    let text = " Nothing to change here. ";
+    assert_eq!(replacements(&[("foo", "bar")]).process(text, None), text);
+    assert_eq!(filter_words(&["um"]).process(text, None), text);
+    assert_eq!(PunctuationCommands::default().process(text, None), text);
+}
+
+#[test]
+fn test_commands_found_for_language_name() {
+    let commands = PunctuationCommands::new(PunctuationMode::Spoken);
+    assert_eq!(
+        commands.process("ahoj čiarka svet", Some("slovak")),
+        "ahoj, svet"
+    );
+}
@@ -0,0 +1,113 @@
+//! Splitting text into words for phrase matching.
+
+/// A whitespace-separated word and the punctuation around it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Word<'a> {
+    /// Whitespace before the word.
+    pub space: &'a str,
+    /// Punctuation before the letters, e.g. an opening quote.
+    pub lead: &'a str,
+    /// Letters and digits, with any punctuation between them.
+    pub core: &'a str,
+    /// Punctuation after the letters, e.g. a comma.
+    pub trail: &'a str,
+}
+
+impl Word<'_> {
+    /// Append the word without the whitespace before it.
+    pub fn push_to(&self, out: &mut String) {
+        out.push_str(self.lead);
+        out.push_str(self.core);
+        out.push_str(self.trail);
+    }
+}
+
+/// Split `text` into words. Whitespace at the end becomes a word of its own
+/// with nothing but `space`, so joining the words gives back `text`.
+pub(super) fn split(text: &str) -> Vec<Word<'_>> {
+    let mut words = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let start = rest
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(rest.len());
+        let (space, after) = rest.split_at(start);
+        let end = after.find(char::is_whitespace).unwrap_or(after.len());
+        let (word, remaining) = after.split_at(end);
+
+        let core_start = word.find(char::is_alphanumeric).unwrap_or(word.len());
+        let core_end = word
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_alphanumeric())
+            .map_or(core_start, |(i, c)| i + c.len_utf8());
+        words.push(Word {
+            space,
+            lead: &word[..core_start],
+            core: &word[core_start..core_end],
+            trail: &word[core_end..],
+        });
+        rest = remaining;
+    }
+    words
+}
+
+/// Lowercase words of `phrase` as matched by [`longest_match`].
+pub(super) fn phrase(phrase: &str) -> Vec<String> {
+    split(phrase)
+        .iter()
+        .filter(|word| !word.core.is_empty())
+        .map(|word| word.core.to_lowercase())
+        .collect()
+}
+
+/// Find the phrase `words` start with, ignoring case and punctuation.
+///
+/// `phrases` must be sorted longest first so the longest match wins.
+/// Returns how many words matched and the phrase's value.
+pub(super) fn longest_match<'p, T>(
+    words: &[Word<'_>],
+    phrases: &'p [(Vec<String>, T)],
+) -> Option<(usize, &'p T)> {
+    phrases.iter().find_map(|(phrase, value)| {
+        let matched = !phrase.is_empty()
+            && words.len() >= phrase.len()
+            && words
+                .iter()
+                .zip(phrase)
+                .all(|(word, key)| word.core.to_lowercase() == *key);
+        matched.then_some((phrase.len(), value))
+    })
+}
+
+/// Sort phrases longest first, as [`longest_match`] expects.
+pub(super) fn sort_phrases<T>(phrases: &mut [(Vec<String>, T)]) {
+    phrases.sort_by_key(|(phrase, _)| std::cmp::Reverse(phrase.len()));
+}
+
+/// Uppercase the first letter of `word`.
+pub fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Lowercase the first letter of a word the model capitalized only to start
+/// a sentence. Names and acronyms with more capitals, and "I", are kept.
+pub fn uncapitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first)
+            if first.is_uppercase()
+                && !chars.as_str().chars().any(char::is_uppercase)
+                && word != "I"
+                && !word.starts_with("I'")
+                && !word.starts_with("I’") =>
+        {
+            first.to_lowercase().chain(chars).collect()
+        }
+        _ => word.to_string(),
+    }
+}
@@ -0,0 +1,263 @@
+//! Speech segmentation on top of the VAD state machine.
+//!
+//! Turns a stream of 16kHz chunks and their speech probabilities into whole
+//! utterances, keeping some audio from before the detected start (pre-roll)
+//! and after the last voiced chunk (post-roll) so word edges are not clipped.
+
+use crate::vad::{VAD_SAMPLE_RATE, VadConfig, VadEvent, VadStateMachine};
+use std::collections::VecDeque;
+
+/// A finished utterance ready for transcription.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeechSegment {
+    /// Mono samples at 16kHz, including pre- and post-roll.
+    pub samples: Vec<f32>,
+    /// Number of leading samples taken from the pre-roll buffer.
+    pub pre_roll: usize,
+}
+
+impl SpeechSegment {
+    /// Duration in seconds from the detected speech start, excluding pre-roll.
+    pub fn speech_duration_secs(&self) -> f32 {
+        (self.samples.len() - self.pre_roll) as f32 / VAD_SAMPLE_RATE as f32
+    }
+}
+
+/// Longest trailing window searched for a split point when a segment is too long.
+const SPLIT_WINDOW_MS: u32 = 2000;
+
+/// Position and speech probability of one chunk within the current segment.
+#[derive(Debug, Clone, Copy)]
+struct ChunkScore {
+    /// Sample offset just past the end of the chunk.
+    end: usize,
+    probability: f32,
+}
+
+/// Convert a duration in milliseconds to a sample count at 16kHz.
+pub fn ms_to_samples(ms: u32) -> usize {
+    (VAD_SAMPLE_RATE as usize * ms as usize) / 1000
+}
+
+/// Groups VAD chunks into speech segments.
+#[derive(Debug)]
+pub struct SpeechSegmenter {
+    state_machine: VadStateMachine,
+    end_threshold: f32,
+    pre_roll_len: usize,
+    post_roll_len: usize,
+    /// Segments are split once they reach this many samples.
+    max_len: usize,
+    /// How far back from the end of a too-long segment to look for a split point.
+    split_window: usize,
+    /// Most recent audio, capped at `pre_roll_len` samples.
+    history: VecDeque<f32>,
+    /// Utterance being collected, if any.
+    current: Option<SpeechSegment>,
+    /// Chunks of `current` after speech start, with their probabilities.
+    scores: Vec<ChunkScore>,
+    /// Samples at the end of `current` since the last voiced chunk.
+    trailing_silence: usize,
+    /// Speech has ended; only the post-roll tail is still being collected.
+    in_tail: bool,
+}
+
+impl SpeechSegmenter {
+    /// Create a segmenter keeping `pre_roll_len` samples before speech start
+    /// and `post_roll_len` samples after the last voiced chunk.
+    pub fn new(config: VadConfig, pre_roll_len: usize, post_roll_len: usize) -> Self {
+        Self {
+            end_threshold: config.end_threshold,
+            state_machine: VadStateMachine::new(config),
+            pre_roll_len,
+            post_roll_len,
+            max_len: usize::MAX,
+            split_window: 0,
+            history: VecDeque::with_capacity(pre_roll_len),
+            current: None,
+            scores: Vec::new(),
+            trailing_silence: 0,
+            in_tail: false,
+        }
+    }
+
+    /// Split segments that reach `max_len` samples, even mid-speech.
+    ///
+    /// The split falls after the least speech-like chunk near the end, so a
+    /// long utterance is most likely cut at a pause between words.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self.split_window = (max_len / 2).min(ms_to_samples(SPLIT_WINDOW_MS));
+        self
+    }
+
+    /// Feed one chunk with its speech probability.
+    ///
+    /// Returns a segment once speech has ended and its post-roll is complete.
+    pub fn push(&mut self, chunk: &[f32], probability: f32) -> Option<SpeechSegment> {
+        let event = self.state_machine.process(probability);
+
+        let finished = match event {
+            // Speech resumed while collecting the tail: close the previous
+            // segment as is and start a new one.
+            Some(VadEvent::SpeechStart) => {
+                let finished = self.current.take().map(|segment| self.close(segment));
+                self.start(chunk, probability);
+                finished
+            }
+            _ => {
+                self.extend(chunk, probability);
+                if event == Some(VadEvent::SpeechEnd) {
+                    self.in_tail = true;
+                }
+                if self.in_tail && self.trailing_silence >= self.post_roll_len {
+                    self.current.take().map(|segment| self.close(segment))
+                } else if self.current_len() >= self.max_len {
+                    self.split()
+                } else {
+                    None
+                }
+            }
+        };
+
+        self.remember(chunk);
+        finished
+    }
+
+    /// Finish the segment in progress, e.g. when the audio source ends.
+    pub fn flush(&mut self) -> Option<SpeechSegment> {
+        let segment = self.current.take()?;
+        let segment = self.close(segment);
+        self.state_machine.reset();
+        Some(segment)
+    }
+
+    /// Whether a segment is currently being collected.
+    pub fn is_speaking(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// The segment collected so far, if speech is in progress.
+    pub fn pending(&self) -> Option<&SpeechSegment> {
+        self.current.as_ref()
+    }
+
+    /// Drop all buffered audio and reset the state machine.
+    pub fn reset(&mut self) {
+        self.state_machine.reset();
+        self.history.clear();
+        self.current = None;
+        self.scores.clear();
+        self.trailing_silence = 0;
+        self.in_tail = false;
+    }
+
+    /// Length of the segment in progress, in samples.
+    fn current_len(&self) -> usize {
+        self.current
+            .as_ref()
+            .map_or(0, |segment| segment.samples.len())
+    }
+
+    /// Split an over-long segment; speech still in progress continues in
+    /// the remainder.
+    fn split(&mut self) -> Option<SpeechSegment> {
+        if self.in_tail {
+            let segment = self.current.take()?;
+            return Some(self.close(segment));
+        }
+
+        let at = self.split_point();
+        let current = self.current.as_mut()?;
+        let rest = current.samples.split_off(at);
+        let samples = std::mem::replace(&mut current.samples, rest);
+        let finished = SpeechSegment {
+            samples,
+            pre_roll: std::mem::take(&mut current.pre_roll),
+        };
+
+        let remaining = current.samples.len();
+        self.scores.retain(|score| score.end > at);
+        for score in &mut self.scores {
+            score.end -= at;
+        }
+        self.trailing_silence = self.trailing_silence.min(remaining);
+        Some(finished)
+    }
+
+    /// Sample offset right after the lowest-probability chunk in the split
+    /// window. Ties go to the latest chunk, keeping segments as long as possible.
+    fn split_point(&self) -> usize {
+        let len = self.current_len();
+        let window_start = len.saturating_sub(self.split_window);
+        self.scores
+            .iter()
+            .filter(|score| score.end > window_start)
+            .reduce(|best, score| {
+                if score.probability <= best.probability {
+                    score
+                } else {
+                    best
+                }
+            })
+            .map_or(len, |score| score.end)
+    }
+
+    /// Begin a new segment with the pre-roll history followed by `chunk`.
+    fn start(&mut self, chunk: &[f32], probability: f32) {
+        let mut samples: Vec<f32> = self.history.iter().copied().collect();
+        let pre_roll = samples.len();
+        samples.extend_from_slice(chunk);
+        self.scores = vec![ChunkScore {
+            end: samples.len(),
+            probability,
+        }];
+        self.current = Some(SpeechSegment { samples, pre_roll });
+        self.trailing_silence = 0;
+        self.in_tail = false;
+    }
+
+    /// Append `chunk` to the segment in progress, if any.
+    fn extend(&mut self, chunk: &[f32], probability: f32) {
+        let Some(segment) = self.current.as_mut() else {
+            return;
+        };
+        segment.samples.extend_from_slice(chunk);
+        self.scores.push(ChunkScore {
+            end: segment.samples.len(),
+            probability,
+        });
+        if probability >= self.end_threshold {
+            self.trailing_silence = 0;
+        } else {
+            self.trailing_silence += chunk.len();
+        }
+    }
+
+    /// Trim trailing silence beyond the post-roll and reset segment state.
+    fn close(&mut self, mut segment: SpeechSegment) -> SpeechSegment {
+        let excess = self.trailing_silence.saturating_sub(self.post_roll_len);
+        let min_len = segment.pre_roll;
+        let len = segment.samples.len().saturating_sub(excess).max(min_len);
+        segment.samples.truncate(len);
+        self.scores.clear();
+        self.trailing_silence = 0;
+        self.in_tail = false;
+        segment
+    }
+
+    /// Add `chunk` to the pre-roll ring buffer.
+    fn remember(&mut self, chunk: &[f32]) {
+        if self.pre_roll_len == 0 {
+            return;
+        }
+        let keep = chunk.len().min(self.pre_roll_len);
+        self.history.extend(&chunk[chunk.len() - keep..]);
+        let overflow = self.history.len().saturating_sub(self.pre_roll_len);
+        self.history.drain(..overflow);
+    }
+}
+
+#[cfg(test)]
+#[path = "segment_test.rs"]
+mod tests;
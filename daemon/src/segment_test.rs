@@ -0,0 +1,217 @@
+use super::*;
+
+const CHUNK: usize = 4;
+
+fn test_config() -> VadConfig {
+    VadConfig {
+        threshold: 0.5,
+        end_threshold: 0.5,
+        min_speech_chunks: 2,
+        min_silence_chunks: 3,
+    }
+}
+
+/// Feed one chunk per probability; chunk `i` is filled with the value `i`.
+fn run(segmenter: &mut SpeechSegmenter, probabilities: &[f32]) -> Vec<SpeechSegment> {
+    probabilities
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &p)| segmenter.push(&[i as f32; CHUNK], p))
+        .collect()
+}
+
+/// Chunk indices a segment is made of, in order.
+fn chunk_ids(segment: &SpeechSegment) -> Vec<usize> {
+    segment
+        .samples
+        .chunks(CHUNK)
+        .map(|chunk| chunk[0] as usize)
+        .collect()
+}
+
+#[test]
+fn test_pre_roll_prepends_audio_before_speech_start() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), 2 * CHUNK, 0);
+    // SpeechStart fires on chunk 4, the second voiced chunk
+    let segments = run(
+        &mut segmenter,
+        &[0.1, 0.1, 0.1, 0.9, 0.9, 0.9, 0.1, 0.1, 0.1],
+    );
+
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].pre_roll, 2 * CHUNK);
+    assert_eq!(chunk_ids(&segments[0]), vec![2, 3, 4, 5]);
+}
+
+#[test]
+fn test_no_pre_roll_starts_at_speech_start() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), 0, 0);
+    let segments = run(&mut segmenter, &[0.1, 0.9, 0.9, 0.9, 0.1, 0.1, 0.1]);
+
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].pre_roll, 0);
+    assert_eq!(chunk_ids(&segments[0]), vec![2, 3]);
+}
+
+#[test]
+fn test_pre_roll_shorter_than_history_is_capped() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), CHUNK / 2, 0);
+    let segments = run(&mut segmenter, &[0.1, 0.9, 0.9, 0.1, 0.1, 0.1]);
+
+    assert_eq!(segments[0].pre_roll, CHUNK / 2);
+    assert_eq!(&segments[0].samples[..CHUNK / 2], &[1.0, 1.0]);
+}
+
+#[test]
+fn test_post_roll_trims_excess_trailing_silence() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), 0, CHUNK);
+    // SpeechEnd fires after three silent chunks; only one is kept
+    let segments = run(&mut segmenter, &[0.9, 0.9, 0.9, 0.1, 0.1, 0.1]);
+
+    assert_eq!(segments.len(), 1);
+    assert_eq!(chunk_ids(&segments[0]), vec![1, 2, 3]);
+}
+
+#[test]
+fn test_post_roll_longer_than_silence_window_waits_for_tail() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), 0, 5 * CHUNK);
+    // SpeechEnd at chunk 5, but five silent chunks are needed for the tail
+    let segments = run(&mut segmenter, &[0.9, 0.9, 0.1, 0.1, 0.1]);
+    assert!(segments.is_empty());
+    assert!(segmenter.is_speaking());
+
+    let segment = segmenter.push(&[5.0; CHUNK], 0.1);
+    assert!(segment.is_none());
+    let segment = segmenter.push(&[6.0; CHUNK], 0.1).unwrap();
+    assert_eq!(chunk_ids(&segment), vec![1, 2, 3, 4, 5, 6]);
+    assert!(!segmenter.is_speaking());
+}
+
+#[test]
+fn test_speech_resuming_in_tail_starts_new_segment() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), CHUNK, 10 * CHUNK);
+    let segments = run(
+        &mut segmenter,
+        &[0.9, 0.9, 0.1, 0.1, 0.1, 0.9, 0.9, 0.1, 0.1, 0.1],
+    );
+
+    assert_eq!(segments.len(), 1);
+    assert_eq!(chunk_ids(&segments[0]), vec![0, 1, 2, 3, 4, 5]);
+    assert!(segmenter.is_speaking());
+
+    let second = segmenter.flush().unwrap();
+    assert_eq!(second.pre_roll, CHUNK);
+    assert_eq!(chunk_ids(&second), vec![5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn test_flush_returns_segment_in_progress() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), 0, 0);
+    assert!(run(&mut segmenter, &[0.9, 0.9, 0.9]).is_empty());
+
+    let segment = segmenter.flush().unwrap();
+    assert_eq!(chunk_ids(&segment), vec![1, 2]);
+    assert!(!segmenter.is_speaking());
+    assert_eq!(segmenter.flush(), None);
+}
+
+#[test]
+fn test_flush_when_idle_returns_none() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), CHUNK, 0);
+    run(&mut segmenter, &[0.1, 0.9, 0.1]);
+    assert_eq!(segmenter.flush(), None);
+}
+
+#[test]
+fn test_pending_exposes_segment_in_progress() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), CHUNK, 0);
+    run(&mut segmenter, &[0.1, 0.9]);
+    assert_eq!(segmenter.pending(), None);
+
+    segmenter.push(&[2.0; CHUNK], 0.9);
+    segmenter.push(&[3.0; CHUNK], 0.9);
+    let pending = segmenter.pending().unwrap();
+    assert_eq!(pending.pre_roll, CHUNK);
+    assert_eq!(chunk_ids(pending), vec![1, 2, 3]);
+}
+
+#[test]
+fn test_reset_drops_history_and_segment() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), 4 * CHUNK, 0);
+    run(&mut segmenter, &[0.1, 0.1, 0.9, 0.9]);
+    assert!(segmenter.is_speaking());
+
+    segmenter.reset();
+    assert!(!segmenter.is_speaking());
+
+    let segments = run(&mut segmenter, &[0.9, 0.9, 0.1, 0.1, 0.1]);
+    assert_eq!(segments[0].pre_roll, CHUNK);
+}
+
+#[test]
+fn test_max_len_cuts_continuous_speech() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), 0, 0).with_max_len(3 * CHUNK);
+    let segments = run(&mut segmenter, &[0.9; 8]);
+
+    // Starts at chunk 1; cut every three chunks while speech continues
+    assert_eq!(segments.len(), 2);
+    assert_eq!(chunk_ids(&segments[0]), vec![1, 2, 3]);
+    assert_eq!(chunk_ids(&segments[1]), vec![4, 5, 6]);
+    assert!(segmenter.is_speaking());
+    assert_eq!(chunk_ids(&segmenter.flush().unwrap()), vec![7]);
+}
+
+#[test]
+fn test_max_len_splits_after_lowest_probability_chunk() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), CHUNK, 0).with_max_len(6 * CHUNK);
+    // Chunk 4 dips but stays voiced; it is the best split point near the end
+    let segments = run(&mut segmenter, &[0.9, 0.9, 0.9, 0.9, 0.6, 0.9, 0.9]);
+
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].pre_roll, CHUNK);
+    assert_eq!(chunk_ids(&segments[0]), vec![0, 1, 2, 3, 4]);
+
+    let rest = segmenter.flush().unwrap();
+    assert_eq!(rest.pre_roll, 0);
+    assert_eq!(chunk_ids(&rest), vec![5, 6]);
+}
+
+#[test]
+fn test_split_only_searches_trailing_window() {
+    let mut segmenter = SpeechSegmenter::new(test_config(), 0, 0).with_max_len(6 * CHUNK);
+    // The dip at chunk 2 is outside the last half of the segment
+    let segments = run(&mut segmenter, &[0.9, 0.9, 0.55, 0.9, 0.8, 0.9, 0.9]);
+
+    assert_eq!(segments.len(), 1);
+    assert_eq!(chunk_ids(&segments[0]), vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn test_split_segments_stay_bounded() {
+    let max_len = 5 * CHUNK;
+    let mut segmenter = SpeechSegmenter::new(test_config(), 2 * CHUNK, 0).with_max_len(max_len);
+    let probabilities: Vec<f32> = (0..200).map(|i| 0.6 + (i % 7) as f32 * 0.05).collect();
+    let segments = run(&mut segmenter, &probabilities);
+
+    assert!(segments.len() > 30);
+    for segment in &segments {
+        assert!(segment.samples.len() <= max_len);
+        assert!(!segment.samples.is_empty());
+    }
+}
+
+#[test]
+fn test_speech_duration_excludes_pre_roll() {
+    let segment = SpeechSegment {
+        samples: vec![0.0; 16000 + 4800],
+        pre_roll: 4800,
+    };
+    assert!((segment.speech_duration_secs() - 1.0).abs() < f32::EPSILON);
+}
+
+#[test]
+fn test_ms_to_samples() {
+    assert_eq!(ms_to_samples(0), 0);
+    assert_eq!(ms_to_samples(300), 4800);
+    assert_eq!(ms_to_samples(1000), 16000);
+}
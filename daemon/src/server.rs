@@ -6,11 +6,15 @@ use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::{Stream, StreamExt};
 use tonic::{Request, Response, Status};
 use vcm_proto::{
-    Empty, Event, GetLanguageResponse, Healthy, SetLanguageRequest, State,
+    AddVocabularyTermsRequest, AddVocabularyTermsResponse, AudioDevice, Empty, Event,
+    GetLanguageResponse, Healthy, ListAudioDevicesResponse, SetLanguageRequest, SetModelRequest,
+    SetTaskRequest, State, UndoLastResponse,
     vcm_server::{Vcm, VcmServer},
 };
 
+use crate::config::{SpeechModel, Task};
 use crate::controller::{Controller, ControllerState};
+use crate::engine::speech_model_to_model_id;
 
 /// gRPC service implementation.
 pub struct VcmService {
@@ -69,6 +73,19 @@ impl Vcm for VcmService {
         Ok(Response::new(Empty {}))
     }
 
+    async fn undo_last(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<UndoLastResponse>, Status> {
+        let deleted = self
+            .controller
+            .undo_last()
+            .map_err(Status::failed_precondition)?;
+        Ok(Response::new(UndoLastResponse {
+            deleted_chars: deleted as u32,
+        }))
+    }
+
     async fn get_status(
         &self,
         _request: Request<Empty>,
@@ -80,14 +97,57 @@ impl Vcm for VcmService {
             ControllerState::Listening => State::Listening,
             ControllerState::Paused => State::Paused,
         };
+        let detected = self.controller.detected_language();
+        let filter_counts = self.controller.filter_counts();
         let status = vcm_proto::Status {
             status: Some(vcm_proto::status::Status::Healthy(Healthy {
                 state: proto_state.into(),
+                detected_language: detected
+                    .as_ref()
+                    .map(|d| d.code.clone())
+                    .unwrap_or_default(),
+                detected_language_probability: detected
+                    .map(|d| f64::from(d.probability))
+                    .unwrap_or_default(),
+                filter_counts: Some(vcm_proto::FilterCounts {
+                    blocklisted: filter_counts.blocklisted,
+                    repetitions: filter_counts.repetitions,
+                    too_fast: filter_counts.too_fast,
+                }),
+                model: speech_model_to_model_id(self.controller.model().await).to_string(),
+                models_unloaded: self.controller.models_unloaded(),
             })),
         };
         Ok(Response::new(status))
     }
 
+    async fn list_audio_devices(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<ListAudioDevicesResponse>, Status> {
+        let list = self
+            .controller
+            .list_audio_devices()
+            .await
+            .map_err(Status::unavailable)?;
+        let devices = list
+            .devices
+            .into_iter()
+            .enumerate()
+            .map(|(i, d)| AudioDevice {
+                id: d.id,
+                name: d.name,
+                is_default: d.is_default,
+                is_selected: list.selected == Some(i),
+            })
+            .collect();
+        Ok(Response::new(ListAudioDevicesResponse {
+            devices,
+            configured_device: list.configured.unwrap_or_default(),
+            configured_device_missing: list.configured_missing,
+        }))
+    }
+
     type SubscribeStream = Pin<Box<dyn Stream<Item = Result<Event, Status>> + Send>>;
 
     #[allow(clippy::result_large_err)]
@@ -113,14 +173,71 @@ impl Vcm for VcmService {
         Ok(Response::new(Empty {}))
     }
 
+    async fn set_task(&self, request: Request<SetTaskRequest>) -> Result<Response<Empty>, Status> {
+        let task = match request.into_inner().task() {
+            vcm_proto::Task::Transcribe => Task::Transcribe,
+            vcm_proto::Task::Translate => Task::Translate,
+        };
+        self.controller
+            .set_task(task)
+            .await
+            .map_err(Status::invalid_argument)?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn set_model(
+        &self,
+        request: Request<SetModelRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let model: SpeechModel = request
+            .into_inner()
+            .model
+            .parse()
+            .map_err(|e: anyhow::Error| Status::invalid_argument(e.to_string()))?;
+        self.controller
+            .set_model(model)
+            .await
+            .map_err(Status::failed_precondition)?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn add_vocabulary_terms(
+        &self,
+        request: Request<AddVocabularyTermsRequest>,
+    ) -> Result<Response<AddVocabularyTermsResponse>, Status> {
+        let request = request.into_inner();
+        let language = Some(request.language.as_str()).filter(|l| !l.is_empty());
+        let added = self
+            .controller
+            .add_vocabulary_terms(&request.terms, language)
+            .await
+            .map_err(Status::invalid_argument)?;
+        Ok(Response::new(AddVocabularyTermsResponse {
+            added: added as u32,
+        }))
+    }
+
+    async fn reload_config(&self, _request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        self.controller
+            .reload_config()
+            .await
+            .map_err(Status::failed_precondition)?;
+        Ok(Response::new(Empty {}))
+    }
+
     async fn get_language(
         &self,
         _request: Request<Empty>,
     ) -> Result<Response<GetLanguageResponse>, Status> {
         let (language, available) = self.controller.get_language_info().await;
+        let task = match self.controller.task().await {
+            Task::Transcribe => vcm_proto::Task::Transcribe,
+            Task::Translate => vcm_proto::Task::Translate,
+        };
         Ok(Response::new(GetLanguageResponse {
             language,
             available_languages: available,
+            task: task.into(),
         }))
     }
 }
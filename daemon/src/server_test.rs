@@ -12,3 +12,22 @@ fn test_service_creation() {
     let controller = Arc::new(Controller::new(tx, shutdown_tx, engine, config));
     let _service = VcmService::new(controller);
 }
+
+#[tokio::test]
+async fn test_get_language_reports_task() {
+    for (task, expected) in [
+        (Task::Transcribe, vcm_proto::Task::Transcribe),
+        (Task::Translate, vcm_proto::Task::Translate),
+    ] {
+        let (tx, _rx) = broadcast::channel(16);
+        let (shutdown_tx, _shutdown_rx) = oneshot::channel();
+        let mut config = Config::default();
+        config.model.task = task;
+        let engine = Engine::new(config.clone()).unwrap();
+        let controller = Arc::new(Controller::new(tx, shutdown_tx, engine, config));
+        let service = VcmService::new(controller);
+
+        let response = service.get_language(Request::new(Empty {})).await.unwrap();
+        assert_eq!(response.get_ref().task(), expected);
+    }
+}
@@ -0,0 +1,85 @@
+//! Stable-prefix tracking for streaming partial transcriptions.
+//!
+//! Partials for the same utterance are re-transcriptions of a growing window,
+//! so their tail keeps changing. Words that two consecutive partials agree on
+//! are treated as stable and may be injected before the utterance ends.
+
+/// Tracks what has been injected for the utterance in progress.
+#[derive(Debug, Default)]
+pub struct PrefixTracker {
+    /// Words of the previous partial.
+    previous: Vec<String>,
+    /// Words already injected for this utterance.
+    committed: Vec<String>,
+}
+
+impl PrefixTracker {
+    /// Create a tracker with nothing injected.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a partial and return newly stable text to inject, if any.
+    ///
+    /// The last word of a partial is never considered stable, since it may
+    /// be cut off mid-word by the window.
+    pub fn on_partial(&mut self, text: &str) -> Option<String> {
+        let words = split_words(text);
+        let agreed = common_prefix_len(&self.previous, &words).min(words.len().saturating_sub(1));
+        self.previous = words;
+
+        // Only extend what was injected; never retract
+        if agreed <= self.committed.len()
+            || common_prefix_len(&self.committed, &self.previous) < self.committed.len()
+        {
+            return None;
+        }
+
+        let new_words = self.previous[self.committed.len()..agreed].to_vec();
+        let delta = join_delta(&self.committed, &new_words);
+        self.committed.extend(new_words);
+        Some(delta)
+    }
+
+    /// Record the final text for the utterance and return what is left to inject.
+    ///
+    /// Resets the tracker for the next utterance. If the final text disagrees
+    /// with words already injected, only the words after the point of
+    /// disagreement are returned.
+    pub fn on_final(&mut self, text: &str) -> String {
+        let words = split_words(text);
+        let agreed = common_prefix_len(&self.committed, &words);
+        let rest = if self.committed.is_empty() {
+            // Line breaks and tabs from spoken commands are typed as keys
+            text.trim_matches(' ').to_string()
+        } else {
+            join_delta(&self.committed[..agreed], &words[agreed..])
+        };
+        self.previous.clear();
+        self.committed.clear();
+        rest
+    }
+}
+
+fn split_words(text: &str) -> Vec<String> {
+    text.split_whitespace().map(str::to_string).collect()
+}
+
+fn common_prefix_len(a: &[String], b: &[String]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+/// Join `words` for appending after `before`, with a leading space when
+/// something precedes them.
+fn join_delta(before: &[String], words: &[String]) -> String {
+    let joined = words.join(" ");
+    if before.is_empty() || joined.is_empty() {
+        joined
+    } else {
+        format!(" {joined}")
+    }
+}
+
+#[cfg(test)]
+#[path = "streaming_test.rs"]
+mod tests;
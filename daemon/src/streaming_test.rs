@@ -0,0 +1,82 @@
+use super::*;
+
+#[test]
+fn test_first_partial_is_never_stable() {
+    let mut tracker = PrefixTracker::new();
+    assert_eq!(tracker.on_partial("hello world"), None);
+}
+
+#[test]
+fn test_words_agreed_by_two_partials_become_stable() {
+    let mut tracker = PrefixTracker::new();
+    tracker.on_partial("hello wor");
+    assert_eq!(
+        tracker.on_partial("hello world how").as_deref(),
+        Some("hello")
+    );
+    assert_eq!(
+        tracker.on_partial("hello world how are").as_deref(),
+        Some(" world how")
+    );
+}
+
+#[test]
+fn test_last_word_is_held_back() {
+    let mut tracker = PrefixTracker::new();
+    tracker.on_partial("hello world");
+    // Both partials agree on both words, but the last one may still grow
+    assert_eq!(tracker.on_partial("hello world").as_deref(), Some("hello"));
+}
+
+#[test]
+fn test_revised_partial_does_not_retract() {
+    let mut tracker = PrefixTracker::new();
+    tracker.on_partial("I scream for");
+    assert_eq!(
+        tracker.on_partial("I scream for ice").as_deref(),
+        Some("I scream for")
+    );
+
+    // Whisper changed its mind about already-injected words
+    tracker.on_partial("ice cream for ice");
+    assert_eq!(tracker.on_partial("ice cream for ice cream"), None);
+}
+
+#[test]
+fn test_final_returns_remainder_after_committed() {
+    let mut tracker = PrefixTracker::new();
+    tracker.on_partial("hello world how");
+    tracker.on_partial("hello world how are");
+    assert_eq!(tracker.on_final("hello world how are you."), " are you.");
+}
+
+#[test]
+fn test_final_without_partials_returns_everything() {
+    let mut tracker = PrefixTracker::new();
+    assert_eq!(tracker.on_final(" Hello there. "), "Hello there.");
+}
+
+#[test]
+fn test_final_keeps_line_breaks_at_the_ends() {
+    let mut tracker = PrefixTracker::new();
+    assert_eq!(tracker.on_final(" Dear Anna,\n"), "Dear Anna,\n");
+}
+
+#[test]
+fn test_final_after_disagreement_returns_words_past_divergence() {
+    let mut tracker = PrefixTracker::new();
+    tracker.on_partial("send the male");
+    tracker.on_partial("send the male to");
+    assert_eq!(tracker.on_final("send the mail to Bob"), " mail to Bob");
+}
+
+#[test]
+fn test_final_resets_for_next_utterance() {
+    let mut tracker = PrefixTracker::new();
+    tracker.on_partial("one two three");
+    tracker.on_partial("one two three four");
+    tracker.on_final("one two three four");
+
+    assert_eq!(tracker.on_partial("five six"), None);
+    assert_eq!(tracker.on_final("five six"), "five six");
+}
@@ -0,0 +1,182 @@
+//! Removal of text Whisper makes up.
+//!
+//! On silence and noise Whisper tends to produce phrases from its training
+//! subtitles ("Thank you for watching.") or to loop on the same words. The
+//! no-speech probability catches some of it; this filter catches known
+//! phrases, loops, and more text than the audio could hold.
+
+use super::{Transcript, TranscriptSegment};
+use crate::config::FilterConfig;
+use std::collections::HashSet;
+use tracing::debug;
+
+/// Why text was removed from a transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterReason {
+    /// A segment matched the blocklist and was dropped.
+    Blocklisted,
+    /// Repeated words in a segment were cut down to one occurrence.
+    Repetition,
+    /// The transcript had more text than the audio could hold and was dropped.
+    TooFast,
+}
+
+/// How often each filter fired.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FilterCounts {
+    pub blocklisted: u64,
+    pub repetitions: u64,
+    pub too_fast: u64,
+}
+
+impl FilterCounts {
+    pub fn record(&mut self, reason: FilterReason) {
+        match reason {
+            FilterReason::Blocklisted => self.blocklisted += 1,
+            FilterReason::Repetition => self.repetitions += 1,
+            FilterReason::TooFast => self.too_fast += 1,
+        }
+    }
+}
+
+/// Removes hallucinated text from transcripts.
+#[derive(Debug, Clone, Default)]
+pub struct HallucinationFilter {
+    /// Normalized blocklist phrases.
+    blocklist: HashSet<String>,
+    /// Most consecutive occurrences of a phrase kept as is; 0 disables.
+    max_repeats: usize,
+    /// Highest plausible rate of non-space characters; 0 disables.
+    max_chars_per_sec: f32,
+}
+
+impl HallucinationFilter {
+    pub fn new(config: &FilterConfig) -> Self {
+        Self {
+            blocklist: config
+                .blocklist
+                .iter()
+                .map(|phrase| normalize(phrase))
+                .filter(|phrase| !phrase.is_empty())
+                .collect(),
+            max_repeats: config.max_repeats as usize,
+            max_chars_per_sec: config.max_chars_per_sec,
+        }
+    }
+
+    /// Remove hallucinated text from a transcript of `audio_secs` of audio.
+    ///
+    /// Returns what was removed, one entry per affected segment.
+    pub fn apply(&self, transcript: &mut Transcript, audio_secs: f32) -> Vec<FilterReason> {
+        if transcript.text.is_empty() {
+            return Vec::new();
+        }
+
+        // Backends without segments get the whole text checked as one
+        let mut segments = if transcript.segments.is_empty() {
+            vec![TranscriptSegment {
+                text: transcript.text.clone(),
+                ..TranscriptSegment::default()
+            }]
+        } else {
+            transcript.segments.clone()
+        };
+
+        let mut reasons = Vec::new();
+        segments.retain_mut(|segment| {
+            if self.blocklist.contains(&normalize(&segment.text)) {
+                debug!(text = %segment.text, "Dropping blocklisted segment");
+                reasons.push(FilterReason::Blocklisted);
+                return false;
+            }
+            if let Some(collapsed) = collapse_repetitions(&segment.text, self.max_repeats) {
+                debug!(text = %segment.text, "Collapsing repeated phrase");
+                reasons.push(FilterReason::Repetition);
+                segment.text = collapsed;
+            }
+            true
+        });
+
+        if !reasons.is_empty() {
+            transcript.text = segments
+                .iter()
+                .map(|segment| segment.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            if !transcript.segments.is_empty() {
+                transcript.segments = segments;
+            }
+        }
+
+        let chars = transcript
+            .text
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .count();
+        if self.max_chars_per_sec > 0.0
+            && audio_secs > 0.0
+            && chars as f32 / audio_secs > self.max_chars_per_sec
+        {
+            debug!(
+                text = %transcript.text,
+                audio_secs = audio_secs,
+                "Dropping transcript with implausible character rate"
+            );
+            transcript.text.clear();
+            transcript.segments.clear();
+            reasons.push(FilterReason::TooFast);
+        }
+
+        reasons
+    }
+}
+
+/// Lowercase `text` and reduce it to words separated by single spaces.
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Cut runs of more than `max_repeats` consecutive occurrences of a phrase
+/// down to its first occurrence. Returns `None` if there are none.
+fn collapse_repetitions(text: &str, max_repeats: usize) -> Option<String> {
+    if max_repeats == 0 {
+        return None;
+    }
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let keys: Vec<String> = words.iter().map(|word| normalize(word)).collect();
+
+    let mut kept = Vec::with_capacity(words.len());
+    let mut changed = false;
+    let mut i = 0;
+    while i < words.len() {
+        let run = (1..=(words.len() - i) / (max_repeats + 1)).find_map(|len| {
+            let phrase = &keys[i..i + len];
+            let repeats = keys[i..]
+                .chunks_exact(len)
+                .take_while(|chunk| *chunk == phrase)
+                .count();
+            (repeats > max_repeats).then_some((len, repeats))
+        });
+        match run {
+            Some((len, repeats)) => {
+                kept.extend_from_slice(&words[i..i + len]);
+                i += len * repeats;
+                changed = true;
+            }
+            None => {
+                kept.push(words[i]);
+                i += 1;
+            }
+        }
+    }
+
+    changed.then(|| kept.join(" "))
+}
+
+#[cfg(test)]
+#[path = "filter_test.rs"]
+mod tests;
@@ -0,0 +1,117 @@
+use super::*;
+
+fn filter() -> HallucinationFilter {
+    HallucinationFilter::new(&FilterConfig::default())
+}
+
+fn segment(text: &str) -> TranscriptSegment {
+    TranscriptSegment {
+        text: text.to_string(),
+        ..TranscriptSegment::default()
+    }
+}
+
+#[test]
+fn test_normalize_ignores_case_and_punctuation() {
+    assert_eq!(
+        normalize("Subtitles by the Amara.org community!"),
+        "subtitles by the amara org community"
+    );
+    assert_eq!(normalize("  ...  "), "");
+}
+
+#[test]
+fn test_blocklisted_transcript_is_cleared() {
+    let mut transcript = Transcript::from_text("thank you for watching");
+    let reasons = filter().apply(&mut transcript, 2.0);
+    assert_eq!(reasons, vec![FilterReason::Blocklisted]);
+    assert_eq!(transcript.text, "");
+}
+
+#[test]
+fn test_blocklist_only_drops_matching_segments() {
+    let mut transcript = Transcript {
+        text: "Send the report today. Thanks for watching!".to_string(),
+        segments: vec![
+            segment("Send the report today."),
+            segment("Thanks for watching!"),
+        ],
+        ..Transcript::default()
+    };
+    let reasons = filter().apply(&mut transcript, 4.0);
+    assert_eq!(reasons, vec![FilterReason::Blocklisted]);
+    assert_eq!(transcript.text, "Send the report today.");
+    assert_eq!(transcript.segments, vec![segment("Send the report today.")]);
+}
+
+#[test]
+fn test_blocklisted_phrase_inside_sentence_is_kept() {
+    let mut transcript = Transcript::from_text("I said thank you for watching the kids.");
+    assert!(filter().apply(&mut transcript, 3.0).is_empty());
+    assert_eq!(transcript.text, "I said thank you for watching the kids.");
+}
+
+#[test]
+fn test_repeated_sentence_is_collapsed() {
+    let mut transcript = Transcript::from_text(
+        "Let's meet tomorrow. I'll be there. I'll be there. I'll be there. I'll be there. I'll be there.",
+    );
+    let reasons = filter().apply(&mut transcript, 5.0);
+    assert_eq!(reasons, vec![FilterReason::Repetition]);
+    assert_eq!(transcript.text, "Let's meet tomorrow. I'll be there.");
+}
+
+#[test]
+fn test_short_repetition_is_kept() {
+    let mut transcript = Transcript::from_text("no, no, no, that's wrong");
+    assert!(filter().apply(&mut transcript, 2.0).is_empty());
+    assert_eq!(transcript.text, "no, no, no, that's wrong");
+}
+
+#[test]
+fn test_collapse_repetitions_disabled_with_zero() {
+    assert_eq!(collapse_repetitions("a a a a a a a", 0), None);
+    assert_eq!(
+        collapse_repetitions("a a a a a a a", 4),
+        Some("a".to_string())
+    );
+    assert_eq!(collapse_repetitions("a b a b", 1), Some("a b".to_string()));
+}
+
+#[test]
+fn test_too_much_text_for_audio_is_dropped() {
+    let mut transcript =
+        Transcript::from_text("This is far too much text for half a second of audio.");
+    let reasons = filter().apply(&mut transcript, 0.5);
+    assert_eq!(reasons, vec![FilterReason::TooFast]);
+    assert_eq!(transcript.text, "");
+}
+
+#[test]
+fn test_empty_filter_keeps_everything() {
+    let filter = HallucinationFilter::new(&FilterConfig {
+        blocklist: Vec::new(),
+        max_repeats: 0,
+        max_chars_per_sec: 0.0,
+    });
+    let mut transcript = Transcript::from_text(
+        "Thank you for watching. watching watching watching watching watching",
+    );
+    assert!(filter.apply(&mut transcript, 0.1).is_empty());
+}
+
+#[test]
+fn test_filter_counts_record_reasons() {
+    let mut counts = FilterCounts::default();
+    counts.record(FilterReason::Blocklisted);
+    counts.record(FilterReason::Blocklisted);
+    counts.record(FilterReason::TooFast);
+    assert_eq!(
+        counts,
+        FilterCounts {
+            blocklisted: 2,
+            repetitions: 0,
+            too_fast: 1,
+        }
+    );
+}
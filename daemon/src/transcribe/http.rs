@@ -0,0 +1,283 @@
+//! Transcription by an OpenAI-compatible HTTP server.
+//!
+//! Each utterance is posted as a WAV file to `/v1/audio/transcriptions`, or
+//! `/v1/audio/translations` when translating, the API served by e.g.
+//! faster-whisper-server. When the server fails, utterances go to the local
+//! fallback model for a while before the server is tried again.
+
+use super::{
+    DecodingOptions, Transcriber, Transcript, TranscriptSegment, Vocabulary, language_code,
+};
+use crate::config::{HttpConfig, Task};
+use anyhow::{Context, Result};
+use reqwest::blocking::multipart::{Form, Part};
+use serde::Deserialize;
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// How long the fallback transcribes after the server failed.
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Transcriber sending audio to an HTTP server.
+pub struct HttpTranscriber {
+    client: reqwest::blocking::Client,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+    language: Option<String>,
+    vocabulary: Vocabulary,
+    task: Task,
+    decoding: DecodingOptions,
+    /// Local model used while the server is failing.
+    fallback: Option<Box<dyn Transcriber>>,
+    /// When the server last failed, if it has not answered since.
+    failed_at: Option<Instant>,
+}
+
+impl HttpTranscriber {
+    /// Create a transcriber for the server in `config`, falling back to
+    /// `fallback` when it fails.
+    pub fn new(config: &HttpConfig, fallback: Option<Box<dyn Transcriber>>) -> Result<Self> {
+        info!(url = %config.url, model = %config.model, "Using transcription server");
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms.into()))
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            url: config.url.trim_end_matches('/').to_string(),
+            model: config.model.clone(),
+            api_key: config.api_key.clone(),
+            language: None,
+            vocabulary: Vocabulary::default(),
+            task: Task::default(),
+            decoding: DecodingOptions::default(),
+            fallback,
+            failed_at: None,
+        })
+    }
+
+    /// Endpoint for the current task.
+    fn endpoint(&self) -> String {
+        let path = match self.task {
+            Task::Transcribe => "transcriptions",
+            Task::Translate => "translations",
+        };
+        format!("{}/v1/audio/{path}", self.url)
+    }
+
+    /// Transcribe `audio` on the server.
+    fn request(&self, audio: &[f32], sample_rate: u32) -> Result<Transcript> {
+        let file = Part::bytes(encode_wav(audio, sample_rate)?)
+            .file_name("audio.wav")
+            .mime_str("audio/wav")
+            .context("Invalid audio content type")?;
+        let mut form = Form::new()
+            .part("file", file)
+            .text("model", self.model.clone())
+            .text("response_format", "verbose_json")
+            .text("temperature", self.decoding.temperature.to_string());
+        // Servers expect an ISO 639-1 code, not a language name
+        let language = self.language.as_deref().and_then(language_code);
+        // The translation endpoint always produces English
+        if self.task == Task::Transcribe
+            && let Some(language) = language
+        {
+            form = form.text("language", language);
+        }
+        if let Some(prompt) = self.vocabulary.prompt_for(language) {
+            form = form.text("prompt", prompt.to_string());
+        }
+
+        let mut request = self.client.post(self.endpoint()).multipart(form);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().context("Transcription request failed")?;
+        let status = response.status();
+        let body = response
+            .bytes()
+            .context("Failed to read transcription response")?;
+        if !status.is_success() {
+            anyhow::bail!(
+                "Transcription server returned {status}: {}",
+                String::from_utf8_lossy(&body).trim()
+            );
+        }
+        let response: VerboseTranscription =
+            serde_json::from_slice(&body).context("Invalid transcription response")?;
+        Ok(response.into_transcript(self.decoding.no_speech_threshold))
+    }
+}
+
+impl Transcriber for HttpTranscriber {
+    fn transcribe(&mut self, audio: &[f32], sample_rate: u32) -> Result<Transcript> {
+        debug!(samples = audio.len(), "Transcribing audio on server");
+
+        let server_down = self
+            .failed_at
+            .is_some_and(|failed_at| failed_at.elapsed() < RETRY_AFTER);
+        if self.fallback.is_none() || !server_down {
+            match self.request(audio, sample_rate) {
+                Ok(transcript) => {
+                    if self.failed_at.take().is_some() {
+                        info!("Transcription server is back");
+                    }
+                    return Ok(transcript);
+                }
+                Err(e) if self.fallback.is_some() => {
+                    warn!(
+                        error = format!("{e:#}"),
+                        "Transcription server failed, using the local model"
+                    );
+                    self.failed_at = Some(Instant::now());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        match self.fallback.as_mut() {
+            Some(fallback) => fallback.transcribe(audio, sample_rate),
+            None => anyhow::bail!("No fallback transcriber"),
+        }
+    }
+
+    fn set_language(&mut self, language: Option<String>) {
+        if let Some(fallback) = self.fallback.as_mut() {
+            fallback.set_language(language.clone());
+        }
+        self.language = language;
+    }
+
+    fn set_candidate_languages(&mut self, languages: Vec<String>) {
+        // The API cannot restrict detection; only the fallback uses them
+        if let Some(fallback) = self.fallback.as_mut() {
+            fallback.set_candidate_languages(languages);
+        }
+    }
+
+    fn set_vocabulary(&mut self, vocabulary: Vocabulary) {
+        if let Some(fallback) = self.fallback.as_mut() {
+            fallback.set_vocabulary(vocabulary.clone());
+        }
+        self.vocabulary = vocabulary;
+    }
+
+    fn set_task(&mut self, task: Task) {
+        if let Some(fallback) = self.fallback.as_mut() {
+            fallback.set_task(task);
+        }
+        self.task = task;
+    }
+
+    fn set_decoding(&mut self, options: DecodingOptions) {
+        if let Some(fallback) = self.fallback.as_mut() {
+            fallback.set_decoding(options.clone());
+        }
+        self.decoding = options;
+    }
+}
+
+/// Response in the `verbose_json` format. Servers answering with plain
+/// `json` only send `text`.
+#[derive(Debug, Deserialize)]
+struct VerboseTranscription {
+    text: String,
+    /// Language name ("english") or code.
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    segments: Vec<VerboseSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerboseSegment {
+    /// Start in seconds.
+    start: f64,
+    /// End in seconds.
+    end: f64,
+    text: String,
+    #[serde(default)]
+    avg_logprob: f32,
+    #[serde(default)]
+    no_speech_prob: f32,
+}
+
+impl VerboseTranscription {
+    /// Convert to a transcript, dropping segments more likely silence than
+    /// `no_speech_threshold`.
+    fn into_transcript(self, no_speech_threshold: f32) -> Transcript {
+        let language = self
+            .language
+            .as_deref()
+            .and_then(language_code)
+            .map(str::to_string);
+
+        if self.segments.is_empty() {
+            return Transcript {
+                language,
+                ..Transcript::from_text(self.text.trim())
+            };
+        }
+
+        let mut transcript = Transcript {
+            language,
+            ..Transcript::default()
+        };
+        let mut logprob_sum = 0.0;
+        let mut kept = 0;
+        for segment in self.segments {
+            transcript.no_speech_prob = transcript.no_speech_prob.max(segment.no_speech_prob);
+            if segment.no_speech_prob > no_speech_threshold {
+                debug!(
+                    no_speech_prob = segment.no_speech_prob,
+                    "Dropping segment with high no-speech probability"
+                );
+                continue;
+            }
+            logprob_sum += segment.avg_logprob;
+            kept += 1;
+            transcript.text.push_str(&segment.text);
+            transcript.segments.push(TranscriptSegment {
+                start_ms: seconds_to_ms(segment.start),
+                end_ms: seconds_to_ms(segment.end),
+                text: segment.text.trim().to_string(),
+            });
+        }
+        transcript.text = transcript.text.trim().to_string();
+        if kept > 0 {
+            transcript.avg_logprob = logprob_sum / kept as f32;
+        }
+        transcript
+    }
+}
+
+fn seconds_to_ms(seconds: f64) -> u32 {
+    (seconds.max(0.0) * 1000.0).round() as u32
+}
+
+/// Encode mono samples as a 16-bit WAV file.
+fn encode_wav(audio: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut wav = Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut wav, spec).context("Failed to encode WAV")?;
+    for &sample in audio {
+        writer
+            .write_sample((sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16)
+            .context("Failed to encode WAV")?;
+    }
+    writer.finalize().context("Failed to encode WAV")?;
+    Ok(wav.into_inner())
+}
+
+#[cfg(test)]
+#[path = "http_test.rs"]
+mod tests;
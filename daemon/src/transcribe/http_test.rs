@@ -0,0 +1,233 @@
+use super::*;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+
+/// A request received by the mock server.
+struct Request {
+    head: String,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn body_text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// Answer one request per response on a local port, after `delay`.
+///
+/// Returns the server URL and the requests as they arrive.
+fn serve(responses: Vec<(u16, &'static str)>, delay: Duration) -> (String, Receiver<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (requests_tx, requests_rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for (status, body) in responses {
+            let Ok((stream, _)) = listener.accept() else {
+                return;
+            };
+            let mut reader = BufReader::new(stream);
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().ok())?
+                })
+                .unwrap_or(0);
+            let mut request_body = vec![0; length];
+            reader.read_exact(&mut request_body).unwrap();
+            let _ = requests_tx.send(Request {
+                head,
+                body: request_body,
+            });
+
+            std::thread::sleep(delay);
+            let response = format!(
+                "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = reader.get_mut().write_all(response.as_bytes());
+        }
+    });
+    (url, requests_rx)
+}
+
+fn config(url: &str) -> HttpConfig {
+    HttpConfig {
+        url: url.to_string(),
+        timeout_ms: 2000,
+        ..HttpConfig::default()
+    }
+}
+
+/// Answers every request with the same text.
+struct LocalTranscriber;
+
+impl Transcriber for LocalTranscriber {
+    fn transcribe(&mut self, _audio: &[f32], _sample_rate: u32) -> Result<Transcript> {
+        Ok(Transcript::from_text("local"))
+    }
+
+    fn set_language(&mut self, _language: Option<String>) {}
+}
+
+const VERBOSE_RESPONSE: &str = r#"{
+    "task": "transcribe",
+    "language": "english",
+    "duration": 2.0,
+    "text": " Hello there. General Kenobi.",
+    "segments": [
+        {"start": 0.0, "end": 1.0, "text": " Hello there.", "avg_logprob": -0.2, "no_speech_prob": 0.01},
+        {"start": 1.0, "end": 2.0, "text": " General Kenobi.", "avg_logprob": -0.4, "no_speech_prob": 0.02}
+    ]
+}"#;
+
+#[test]
+fn test_verbose_response_parsed() {
+    let (url, requests) = serve(vec![(200, VERBOSE_RESPONSE)], Duration::ZERO);
+    let mut transcriber = HttpTranscriber::new(&config(&url), None).unwrap();
+    transcriber.set_language(Some("en".to_string()));
+
+    let transcript = transcriber.transcribe(&[0.0; 1600], 16000).unwrap();
+    assert_eq!(transcript.text, "Hello there. General Kenobi.");
+    assert_eq!(transcript.language.as_deref(), Some("en"));
+    assert!((transcript.avg_logprob + 0.3).abs() < 1e-6);
+    assert_eq!(
+        transcript.segments[1],
+        TranscriptSegment {
+            start_ms: 1000,
+            end_ms: 2000,
+            text: "General Kenobi.".to_string(),
+        }
+    );
+
+    let request = requests.recv().unwrap();
+    assert!(request.head.starts_with("POST /v1/audio/transcriptions "));
+    let body = request.body_text();
+    assert!(body.contains("filename=\"audio.wav\""));
+    assert!(body.contains("RIFF"));
+    assert!(body.contains("name=\"model\"\r\n\r\nwhisper-1\r\n"));
+    assert!(body.contains("name=\"response_format\"\r\n\r\nverbose_json\r\n"));
+    assert!(body.contains("name=\"language\"\r\n\r\nen\r\n"));
+}
+
+#[test]
+fn test_plain_json_response_parsed() {
+    let (url, _requests) = serve(vec![(200, r#"{"text": " Hi. "}"#)], Duration::ZERO);
+    let mut transcriber = HttpTranscriber::new(&config(&url), None).unwrap();
+    let transcript = transcriber.transcribe(&[0.0; 1600], 16000).unwrap();
+    assert_eq!(transcript.text, "Hi.");
+    assert!(transcript.segments.is_empty());
+}
+
+#[test]
+fn test_silent_segments_dropped() {
+    let response = r#"{"text": " Thanks.", "segments": [
+        {"start": 0.0, "end": 1.0, "text": " Thanks.", "avg_logprob": -1.0, "no_speech_prob": 0.9}
+    ]}"#;
+    let (url, _requests) = serve(vec![(200, response)], Duration::ZERO);
+    let mut transcriber = HttpTranscriber::new(&config(&url), None).unwrap();
+    let transcript = transcriber.transcribe(&[0.0; 1600], 16000).unwrap();
+    assert_eq!(transcript.text, "");
+    assert!((transcript.no_speech_prob - 0.9).abs() < f32::EPSILON);
+}
+
+#[test]
+fn test_translation_uses_translation_endpoint() {
+    let (url, requests) = serve(vec![(200, r#"{"text": "Hello."}"#)], Duration::ZERO);
+    let mut transcriber = HttpTranscriber::new(&config(&url), None).unwrap();
+    transcriber.set_task(Task::Translate);
+    transcriber.set_language(Some("cs".to_string()));
+    transcriber.transcribe(&[0.0; 1600], 16000).unwrap();
+
+    let request = requests.recv().unwrap();
+    assert!(request.head.starts_with("POST /v1/audio/translations "));
+    assert!(!request.body_text().contains("name=\"language\""));
+}
+
+#[test]
+fn test_language_name_sent_as_code() {
+    let (url, requests) = serve(vec![(200, r#"{"text": ""}"#)], Duration::ZERO);
+    let mut transcriber = HttpTranscriber::new(&config(&url), None).unwrap();
+    transcriber.set_language(Some("slovak".to_string()));
+    transcriber.transcribe(&[0.0; 1600], 16000).unwrap();
+
+    let body = requests.recv().unwrap().body_text();
+    assert!(body.contains("name=\"language\"\r\n\r\nsk\r\n"));
+}
+
+#[test]
+fn test_api_key_sent_as_bearer_token() {
+    let (url, requests) = serve(vec![(200, r#"{"text": ""}"#)], Duration::ZERO);
+    let config = HttpConfig {
+        api_key: Some("secret".to_string()),
+        ..config(&url)
+    };
+    let mut transcriber = HttpTranscriber::new(&config, None).unwrap();
+    transcriber.transcribe(&[0.0; 1600], 16000).unwrap();
+
+    let head = requests.recv().unwrap().head.to_lowercase();
+    assert!(head.contains("authorization: bearer secret"));
+}
+
+#[test]
+fn test_server_error_without_fallback_fails() {
+    let (url, _requests) = serve(vec![(500, r#"{"error": "boom"}"#)], Duration::ZERO);
+    let mut transcriber = HttpTranscriber::new(&config(&url), None).unwrap();
+    let err = transcriber.transcribe(&[0.0; 1600], 16000).unwrap_err();
+    assert!(format!("{err:#}").contains("500"));
+}
+
+#[test]
+fn test_server_error_uses_fallback_until_retry() {
+    let (url, requests) = serve(vec![(500, "{}")], Duration::ZERO);
+    let mut transcriber =
+        HttpTranscriber::new(&config(&url), Some(Box::new(LocalTranscriber))).unwrap();
+
+    assert_eq!(
+        transcriber.transcribe(&[0.0; 1600], 16000).unwrap().text,
+        "local"
+    );
+    assert!(requests.recv().is_ok());
+    // The server is not asked again right away
+    assert_eq!(
+        transcriber.transcribe(&[0.0; 1600], 16000).unwrap().text,
+        "local"
+    );
+    assert!(requests.try_recv().is_err());
+}
+
+#[test]
+fn test_timeout_uses_fallback() {
+    let (url, _requests) = serve(vec![(200, r#"{"text": "late"}"#)], Duration::from_secs(1));
+    let config = HttpConfig {
+        timeout_ms: 100,
+        ..config(&url)
+    };
+    let mut transcriber = HttpTranscriber::new(&config, Some(Box::new(LocalTranscriber))).unwrap();
+    assert_eq!(
+        transcriber.transcribe(&[0.0; 1600], 16000).unwrap().text,
+        "local"
+    );
+}
+
+#[test]
+fn test_encode_wav_roundtrip() {
+    let wav = encode_wav(&[0.0, 0.5, -1.0], 16000).unwrap();
+    let mut reader = hound::WavReader::new(Cursor::new(wav)).unwrap();
+    assert_eq!(reader.spec().sample_rate, 16000);
+    let samples: Vec<i16> = reader.samples().map(|s| s.unwrap()).collect();
+    assert_eq!(samples, vec![0, 16383, -32767]);
+}
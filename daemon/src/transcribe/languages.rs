@@ -0,0 +1,16 @@
+//! Languages Whisper can transcribe.
+
+/// Language code for a code or English name known to Whisper, e.g. `"de"`
+/// for both `"de"` and `"German"`. Looked up in whisper.cpp's own table, so
+/// it matches the languages the model has tokens for.
+pub fn language_code(language: &str) -> Option<&'static str> {
+    let language = language.trim().to_lowercase();
+    if language.is_empty() || language.contains('\0') {
+        return None;
+    }
+    whisper_rs::get_lang_id(&language).and_then(whisper_rs::get_lang_str)
+}
+
+#[cfg(test)]
+#[path = "languages_test.rs"]
+mod tests;
@@ -0,0 +1,26 @@
+use super::*;
+
+#[test]
+fn test_language_code_accepts_codes_and_names() {
+    assert_eq!(language_code("de"), Some("de"));
+    assert_eq!(language_code("German"), Some("de"));
+    assert_eq!(language_code(" haitian creole "), Some("ht"));
+    assert_eq!(language_code("yue"), Some("yue"));
+}
+
+#[test]
+fn test_language_code_rejects_unknown() {
+    assert_eq!(language_code("klingon"), None);
+    assert_eq!(language_code(""), None);
+    assert_eq!(language_code("auto"), None);
+}
+
+#[test]
+fn test_language_code_covers_every_whisper_language() {
+    for id in 0..=whisper_rs::get_lang_max_id() {
+        let code = whisper_rs::get_lang_str(id).unwrap();
+        let name = whisper_rs::get_lang_str_full(id).unwrap();
+        assert_eq!(language_code(code), Some(code));
+        assert_eq!(language_code(name), Some(code), "{name}");
+    }
+}
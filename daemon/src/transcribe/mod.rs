@@ -3,11 +3,153 @@
 //! This module provides a trait abstraction for transcription backends
 //! and implementations for specific models.
 
+use crate::config::Task;
 use anyhow::Result;
+use std::collections::HashMap;
 
+mod filter;
+mod http;
+mod languages;
+mod process;
+mod scripted;
 mod whisper;
+mod worker;
 
+pub use filter::{FilterCounts, FilterReason, HallucinationFilter};
+pub use http::HttpTranscriber;
+pub use languages::language_code;
+pub use process::ProcessTranscriber;
+pub use scripted::{ScriptedRequest, ScriptedTranscriber};
 pub use whisper::WhisperTranscriber;
+pub use worker::{DroppedSegments, JobKind, JobOutput, TranscriptionJob, TranscriptionWorker};
+
+/// Text and decoding statistics for one transcribed piece of audio.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transcript {
+    pub text: String,
+    /// Text as transcribed, when post-processing changed it into `text`.
+    pub raw_text: Option<String>,
+    /// Mean log-probability of the text tokens; `0.0` when there are none.
+    pub avg_logprob: f32,
+    /// Model's probability that the audio contains no speech.
+    pub no_speech_prob: f32,
+    /// Spoken language as an ISO 639-1 code, if known. When translating,
+    /// the text itself is English.
+    pub language: Option<String>,
+    /// Probability of `language`, set when it was detected rather than configured.
+    pub language_probability: Option<f32>,
+    /// Whether the text was translated to English.
+    pub translated: bool,
+    /// Text segments with timings relative to the start of the audio.
+    pub segments: Vec<TranscriptSegment>,
+}
+
+/// A timed piece of a [`Transcript`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscriptSegment {
+    pub start_ms: u32,
+    pub end_ms: u32,
+    pub text: String,
+}
+
+impl Transcript {
+    /// A transcript with only text, e.g. from a backend without statistics.
+    ///
+    /// Its confidence is 1, so clients filtering on confidence keep the text.
+    pub fn from_text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Language of the text: English when translated, otherwise the spoken
+    /// language.
+    pub fn text_language(&self) -> Option<&str> {
+        if self.translated {
+            Some("en")
+        } else {
+            self.language.as_deref()
+        }
+    }
+
+    /// Confidence between 0 and 1: the geometric mean of the text token
+    /// probabilities. Empty transcripts have confidence 0.
+    pub fn confidence(&self) -> f32 {
+        if self.text.is_empty() {
+            return 0.0;
+        }
+        self.avg_logprob.exp().clamp(0.0, 1.0)
+    }
+}
+
+/// How the model decodes audio into text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodingOptions {
+    /// Beam search width; 1 decodes greedily.
+    pub beam_size: u32,
+    /// Candidates sampled at each fallback temperature when decoding greedily.
+    pub best_of: u32,
+    /// Sampling temperature of the first attempt.
+    pub temperature: f32,
+    /// Temperature added for each retry; 0 disables retries.
+    pub temperature_increment: f32,
+    /// CPU threads for inference; `None` lets the backend choose.
+    pub threads: Option<u32>,
+    /// Segments more likely than this to be silence are dropped.
+    pub no_speech_threshold: f32,
+    /// Attempts with a lower token entropy (repetitive text) are retried.
+    pub entropy_threshold: f32,
+    /// Attempts with a lower average token log-probability are retried.
+    pub logprob_threshold: f32,
+    pub suppress_blank: bool,
+    /// Suppress non-speech tokens such as `[MUSIC]`.
+    pub suppress_non_speech: bool,
+    /// Produce a single segment per utterance.
+    pub single_segment: bool,
+    /// Keep the model from writing punctuation, for when it is dictated.
+    pub suppress_punctuation: bool,
+}
+
+impl Default for DecodingOptions {
+    /// Whisper's reference settings, except that non-speech tokens are
+    /// suppressed and each utterance is one segment.
+    fn default() -> Self {
+        Self {
+            beam_size: 1,
+            best_of: 1,
+            temperature: 0.0,
+            temperature_increment: 0.2,
+            threads: None,
+            no_speech_threshold: 0.6,
+            entropy_threshold: 2.4,
+            logprob_threshold: -1.0,
+            suppress_blank: true,
+            suppress_non_speech: true,
+            single_segment: true,
+            suppress_punctuation: false,
+        }
+    }
+}
+
+/// Initial prompts biasing transcription toward known vocabulary.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Vocabulary {
+    /// Prompt for languages without their own entry.
+    pub prompt: Option<String>,
+    /// Prompts by language code.
+    pub languages: HashMap<String, String>,
+}
+
+impl Vocabulary {
+    /// Prompt to use for text in `language`, if any.
+    pub fn prompt_for(&self, language: Option<&str>) -> Option<&str> {
+        language
+            .and_then(|language| self.languages.get(language))
+            .or(self.prompt.as_ref())
+            .map(String::as_str)
+    }
+}
 
 /// Speech-to-text transcriber.
 ///
@@ -20,11 +162,35 @@ pub trait Transcriber: Send {
     /// * `sample_rate` - Sample rate of the audio in Hz (must be 16000)
     ///
     /// # Returns
-    /// The transcribed text, or an error if transcription failed.
-    fn transcribe(&mut self, audio: &[f32], sample_rate: u32) -> Result<String>;
+    /// The transcript, or an error if transcription failed.
+    fn transcribe(&mut self, audio: &[f32], sample_rate: u32) -> Result<Transcript>;
 
     /// Set the language for transcription.
     ///
     /// Pass `None` for automatic language detection, or `Some("en")` etc. for a specific language.
     fn set_language(&mut self, language: Option<String>);
+
+    /// Restrict automatic language detection to these ISO 639-1 codes.
+    ///
+    /// Empty allows any language. Backends that cannot restrict detection ignore this.
+    fn set_candidate_languages(&mut self, _languages: Vec<String>) {}
+
+    /// Set the vocabulary used to prompt the model.
+    ///
+    /// Backends without prompting ignore this.
+    fn set_vocabulary(&mut self, _vocabulary: Vocabulary) {}
+
+    /// Set whether to transcribe or translate to English.
+    ///
+    /// Backends that cannot translate ignore this.
+    fn set_task(&mut self, _task: Task) {}
+
+    /// Set how audio is decoded.
+    ///
+    /// Backends ignore options they do not support.
+    fn set_decoding(&mut self, _options: DecodingOptions) {}
 }
+
+#[cfg(test)]
+#[path = "mod_test.rs"]
+mod tests;
@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn test_confidence_is_token_probability_mean() {
+    let transcript = Transcript {
+        avg_logprob: 0.5f32.ln(),
+        ..Transcript::from_text("hello")
+    };
+    assert!((transcript.confidence() - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn test_confidence_of_empty_transcript_is_zero() {
+    assert_eq!(Transcript::default().confidence(), 0.0);
+}
+
+#[test]
+fn test_vocabulary_prefers_language_prompt() {
+    let vocabulary = Vocabulary {
+        prompt: Some("Base.".to_string()),
+        languages: HashMap::from([("cs".to_string(), "Czech.".to_string())]),
+    };
+    assert_eq!(vocabulary.prompt_for(Some("cs")), Some("Czech."));
+    assert_eq!(vocabulary.prompt_for(Some("en")), Some("Base."));
+    assert_eq!(vocabulary.prompt_for(None), Some("Base."));
+}
+
+#[test]
+fn test_from_text_has_full_confidence() {
+    assert_eq!(Transcript::from_text("hi").confidence(), 1.0);
+}
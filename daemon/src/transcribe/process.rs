@@ -0,0 +1,358 @@
+//! Transcription by a helper program.
+//!
+//! The daemon starts the program once and keeps it running, so models other
+//! than Whisper can be tried without linking them in. Messages on its stdin
+//! and stdout are frames: a little-endian `u32` byte length, then the bytes.
+//!
+//! Each request is two frames, a JSON header and the audio as mono `f32`
+//! little-endian samples. The program answers with one JSON frame:
+//!
+//! ```text
+//! -> {"sample_rate": 16000, "language": "en", "candidate_languages": [],
+//!     "task": "transcribe", "prompt": null}
+//! -> <samples>
+//! <- {"text": "Hello.", "language": "en",
+//!     "segments": [{"start_ms": 0, "end_ms": 900, "text": "Hello."}]}
+//! ```
+//!
+//! Only `text` is required in the answer; `{"error": "..."}` reports a
+//! failure. A program that exits is restarted and the request is sent again
+//! once. One that does not read the request and answer in time is killed
+//! and restarted with the next request.
+
+use super::{Transcriber, Transcript, TranscriptSegment, Vocabulary, language_code};
+use crate::config::{ProcessConfig, Task};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Largest answer accepted, to not allocate whatever a broken program sends.
+const MAX_RESPONSE_BYTES: usize = 16 << 20;
+
+/// Transcriber running requests through a long-lived child process.
+pub struct ProcessTranscriber {
+    config: ProcessConfig,
+    /// The running program; started again on the next request when `None`.
+    /// Dropping it kills the program.
+    child: Option<Child>,
+    language: Option<String>,
+    candidate_languages: Vec<String>,
+    vocabulary: Vocabulary,
+    task: Task,
+    /// How often the program was restarted after crashing or hanging.
+    restarts: u32,
+}
+
+/// Header and audio frames of one request.
+type Request = (Arc<[u8]>, Arc<[u8]>);
+
+/// A started program and its pipes.
+struct Child {
+    process: std::process::Child,
+    /// Requests for the writer thread, which owns stdin. Writing the audio
+    /// blocks once the pipe is full, so it happens off the caller's thread.
+    requests: Sender<Request>,
+    /// Outcome of each write, in request order.
+    written: Receiver<io::Result<()>>,
+    /// Frames read from stdout by a reader thread, ending with the error
+    /// that stopped it.
+    responses: Receiver<io::Result<Vec<u8>>>,
+}
+
+/// Why a request got no transcript.
+enum Failure {
+    /// The program exited or closed its pipes; worth one retry.
+    Crashed(anyhow::Error),
+    /// The program did not answer in time.
+    TimedOut,
+    /// The program answered with an error or garbage.
+    Failed(anyhow::Error),
+}
+
+impl ProcessTranscriber {
+    /// Start the program in `config`.
+    pub fn new(config: &ProcessConfig) -> Result<Self> {
+        let mut transcriber = Self {
+            config: config.clone(),
+            child: None,
+            language: None,
+            candidate_languages: Vec::new(),
+            vocabulary: Vocabulary::default(),
+            task: Task::default(),
+            restarts: 0,
+        };
+        transcriber.child = Some(transcriber.spawn()?);
+        Ok(transcriber)
+    }
+
+    fn spawn(&self) -> Result<Child> {
+        info!(command = %self.config.command, args = ?self.config.args, "Starting transcription process");
+        let mut process = Command::new(&self.config.command)
+            .args(&self.config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start {:?}", self.config.command))?;
+
+        let mut stdin = process.stdin.take().context("Process stdin not piped")?;
+        let mut stdout = process.stdout.take().context("Process stdout not piped")?;
+        let stderr = process.stderr.take().context("Process stderr not piped")?;
+
+        let (requests, requests_rx) = mpsc::channel::<Request>();
+        let (written_tx, written) = mpsc::channel();
+        std::thread::spawn(move || {
+            for (header, pcm) in requests_rx {
+                let result = write_frame(&mut stdin, &header)
+                    .and_then(|()| write_frame(&mut stdin, &pcm))
+                    .and_then(|()| stdin.flush());
+                if written_tx.send(result).is_err() {
+                    break;
+                }
+            }
+        });
+        let (responses_tx, responses) = mpsc::channel();
+        std::thread::spawn(move || {
+            loop {
+                let frame = read_frame(&mut stdout);
+                let stop = frame.is_err();
+                if responses_tx.send(frame).is_err() || stop {
+                    break;
+                }
+            }
+        });
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                debug!(output = %line, "Transcription process");
+            }
+        });
+
+        Ok(Child {
+            process,
+            requests,
+            written,
+            responses,
+        })
+    }
+
+    /// Send one request and wait for the answer. The program is started
+    /// first if it is not running, and killed if it crashed or hung.
+    fn exchange(&mut self, header: &Arc<[u8]>, pcm: &Arc<[u8]>) -> Result<Transcript, Failure> {
+        let mut child = match self.child.take() {
+            Some(child) => child,
+            None => {
+                self.restarts += 1;
+                info!(restarts = self.restarts, "Restarting transcription process");
+                self.spawn().map_err(Failure::Failed)?
+            }
+        };
+        let timeout = Duration::from_millis(self.config.timeout_ms.into());
+        // Dropping the child kills it
+        let frame = child.request(header, pcm, timeout)?;
+        self.child = Some(child);
+
+        let response: Response = serde_json::from_slice(&frame)
+            .context("Invalid answer from transcription process")
+            .map_err(Failure::Failed)?;
+        if let Some(message) = response.error {
+            return Err(Failure::Failed(anyhow::anyhow!(
+                "Transcription process failed: {message}"
+            )));
+        }
+        Ok(response.into_transcript(self.language.as_deref()))
+    }
+
+    fn failure_to_error(&self, failure: Failure) -> anyhow::Error {
+        match failure {
+            Failure::Crashed(e) | Failure::Failed(e) => e,
+            Failure::TimedOut => anyhow::anyhow!(
+                "Transcription process timed out after {} ms",
+                self.config.timeout_ms
+            ),
+        }
+    }
+}
+
+impl Transcriber for ProcessTranscriber {
+    fn transcribe(&mut self, audio: &[f32], sample_rate: u32) -> Result<Transcript> {
+        debug!(samples = audio.len(), "Transcribing audio in process");
+
+        let language = self.language.as_deref().and_then(language_code);
+        let header: Arc<[u8]> = serde_json::to_vec(&RequestHeader {
+            sample_rate,
+            language,
+            candidate_languages: &self.candidate_languages,
+            task: self.task,
+            prompt: self.vocabulary.prompt_for(language),
+        })
+        .context("Failed to encode request")?
+        .into();
+        let pcm: Arc<[u8]> = audio.iter().flat_map(|s| s.to_le_bytes()).collect();
+
+        match self.exchange(&header, &pcm) {
+            Ok(transcript) => Ok(transcript),
+            Err(Failure::Crashed(e)) => {
+                warn!(
+                    error = format!("{e:#}"),
+                    "Transcription process crashed, restarting"
+                );
+                self.exchange(&header, &pcm)
+                    .map_err(|failure| self.failure_to_error(failure))
+            }
+            Err(failure) => Err(self.failure_to_error(failure)),
+        }
+    }
+
+    fn set_language(&mut self, language: Option<String>) {
+        self.language = language;
+    }
+
+    fn set_candidate_languages(&mut self, languages: Vec<String>) {
+        self.candidate_languages = languages;
+    }
+
+    fn set_vocabulary(&mut self, vocabulary: Vocabulary) {
+        self.vocabulary = vocabulary;
+    }
+
+    fn set_task(&mut self, task: Task) {
+        self.task = task;
+    }
+}
+
+impl Child {
+    /// Write a request and read the answer frame, both within `timeout`.
+    fn request(
+        &mut self,
+        header: &Arc<[u8]>,
+        pcm: &Arc<[u8]>,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, Failure> {
+        let deadline = Instant::now() + timeout;
+        let closed = || Failure::Crashed(anyhow::anyhow!("Transcription process closed its input"));
+        self.requests
+            .send((Arc::clone(header), Arc::clone(pcm)))
+            .map_err(|_| closed())?;
+        match self.written.recv_timeout(timeout) {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                return Err(Failure::Crashed(
+                    anyhow::Error::new(e).context("Failed to send audio to transcription process"),
+                ));
+            }
+            Err(RecvTimeoutError::Disconnected) => return Err(closed()),
+            Err(RecvTimeoutError::Timeout) => return Err(Failure::TimedOut),
+        }
+
+        match self
+            .responses
+            .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        {
+            Ok(Ok(frame)) => Ok(frame),
+            Ok(Err(e)) => Err(Failure::Crashed(
+                anyhow::Error::new(e).context("Failed to read from transcription process"),
+            )),
+            Err(RecvTimeoutError::Disconnected) => Err(Failure::Crashed(anyhow::anyhow!(
+                "Transcription process closed its output"
+            ))),
+            Err(RecvTimeoutError::Timeout) => Err(Failure::TimedOut),
+        }
+    }
+}
+
+impl Drop for Child {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+/// First frame of a request.
+#[derive(Debug, Serialize)]
+struct RequestHeader<'a> {
+    sample_rate: u32,
+    /// Language to transcribe, or `None` to detect it.
+    language: Option<&'a str>,
+    /// Codes detection may choose from; empty allows any.
+    candidate_languages: &'a [String],
+    task: Task,
+    /// Text to bias spelling with.
+    prompt: Option<&'a str>,
+}
+
+/// Answer to a request.
+#[derive(Debug, Deserialize)]
+struct Response {
+    #[serde(default)]
+    text: String,
+    /// Spoken language as a code or name.
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    segments: Vec<ResponseSegment>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseSegment {
+    start_ms: u32,
+    end_ms: u32,
+    text: String,
+}
+
+impl Response {
+    /// Convert to a transcript, in `language` if the program reports none.
+    fn into_transcript(self, language: Option<&str>) -> Transcript {
+        let language = self
+            .language
+            .as_deref()
+            .and_then(language_code)
+            .or_else(|| language.and_then(language_code))
+            .map(str::to_string);
+        Transcript {
+            language,
+            segments: self
+                .segments
+                .into_iter()
+                .map(|segment| TranscriptSegment {
+                    start_ms: segment.start_ms,
+                    end_ms: segment.end_ms,
+                    text: segment.text.trim().to_string(),
+                })
+                .collect(),
+            ..Transcript::from_text(self.text.trim())
+        }
+    }
+}
+
+fn write_frame(writer: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+    let length = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Frame too large"))?;
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(payload)
+}
+
+fn read_frame(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut length = [0; 4];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_le_bytes(length) as usize;
+    if length > MAX_RESPONSE_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Frame of {length} bytes is too large"),
+        ));
+    }
+    let mut payload = vec![0; length];
+    reader.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+#[cfg(test)]
+#[path = "process_test.rs"]
+mod tests;
@@ -0,0 +1,157 @@
+use super::*;
+use tempfile::TempDir;
+
+/// Shell function reading one frame into the file `$1`.
+const READ_FRAME: &str = r#"
+read_frame() {
+    n=$(dd bs=1 count=4 2>/dev/null | od -An -tu4 | tr -d ' ')
+    [ -n "$n" ] || exit 0
+    dd bs=1 count="$n" of="$1" 2>/dev/null
+}
+"#;
+
+/// Answer `{"text":" hi"}` to each request, keeping the last request in
+/// `$dir`. With `once`, exit after the first answer.
+fn responder(dir: &TempDir, once: bool) -> ProcessConfig {
+    let body = r#"
+read_frame "$dir/header.json"
+read_frame "$dir/audio.pcm"
+printf '\016\000\000\000{"text":" hi"}'
+"#;
+    let script = if once {
+        format!("{READ_FRAME}{body}")
+    } else {
+        format!("{READ_FRAME}while true; do {body} done")
+    };
+    ProcessConfig {
+        command: "sh".to_string(),
+        args: vec![
+            "-c".to_string(),
+            format!("dir={:?}\n{script}", dir.path().display().to_string()),
+        ],
+        timeout_ms: 5000,
+    }
+}
+
+fn shell(script: &str, timeout_ms: u32) -> ProcessConfig {
+    ProcessConfig {
+        command: "sh".to_string(),
+        args: vec!["-c".to_string(), format!("{READ_FRAME}{script}")],
+        timeout_ms,
+    }
+}
+
+#[test]
+fn test_request_and_answer() {
+    let dir = TempDir::new().unwrap();
+    let mut transcriber = ProcessTranscriber::new(&responder(&dir, false)).unwrap();
+    // Names are sent as codes
+    transcriber.set_language(Some("English".to_string()));
+    transcriber.set_task(Task::Translate);
+
+    let transcript = transcriber.transcribe(&[0.5; 160], 16000).unwrap();
+    assert_eq!(transcript.text, "hi");
+    assert_eq!(transcript.language.as_deref(), Some("en"));
+
+    let header: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(dir.path().join("header.json")).unwrap()).unwrap();
+    assert_eq!(header["sample_rate"], 16000);
+    assert_eq!(header["language"], "en");
+    assert_eq!(header["task"], "translate");
+    let audio = std::fs::read(dir.path().join("audio.pcm")).unwrap();
+    assert_eq!(audio.len(), 160 * 4);
+    assert_eq!(audio[..4], 0.5f32.to_le_bytes());
+
+    // The program keeps running between requests
+    assert_eq!(
+        transcriber.transcribe(&[0.0; 160], 16000).unwrap().text,
+        "hi"
+    );
+    assert_eq!(transcriber.restarts, 0);
+}
+
+#[test]
+fn test_crashed_process_restarted() {
+    let dir = TempDir::new().unwrap();
+    let mut transcriber = ProcessTranscriber::new(&responder(&dir, true)).unwrap();
+
+    assert_eq!(
+        transcriber.transcribe(&[0.0; 160], 16000).unwrap().text,
+        "hi"
+    );
+    assert_eq!(
+        transcriber.transcribe(&[0.0; 160], 16000).unwrap().text,
+        "hi"
+    );
+    assert_eq!(transcriber.restarts, 1);
+}
+
+#[test]
+fn test_timeout_restarts_on_next_request() {
+    let config = shell(
+        "read_frame /dev/null; read_frame /dev/null; exec sleep 5",
+        200,
+    );
+    let mut transcriber = ProcessTranscriber::new(&config).unwrap();
+
+    let err = transcriber.transcribe(&[0.0; 160], 16000).unwrap_err();
+    assert!(format!("{err:#}").contains("timed out"));
+    assert!(transcriber.child.is_none());
+
+    let _ = transcriber.transcribe(&[0.0; 160], 16000);
+    assert_eq!(transcriber.restarts, 1);
+}
+
+#[test]
+fn test_timeout_covers_sending_audio() {
+    // Never reads, so the audio fills the pipe
+    let config = shell("exec sleep 5", 200);
+    let mut transcriber = ProcessTranscriber::new(&config).unwrap();
+
+    let start = Instant::now();
+    let err = transcriber
+        .transcribe(&[0.0; 16000 * 30], 16000)
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("timed out"));
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(transcriber.child.is_none());
+}
+
+#[test]
+fn test_error_answer_reported() {
+    let config = shell(
+        r#"read_frame /dev/null; read_frame /dev/null
+printf '\024\000\000\000{"error":"no model"}'
+exec sleep 5"#,
+        5000,
+    );
+    let mut transcriber = ProcessTranscriber::new(&config).unwrap();
+    let err = transcriber.transcribe(&[0.0; 160], 16000).unwrap_err();
+    assert!(format!("{err:#}").contains("no model"));
+    // An error answer does not restart the program
+    assert!(transcriber.child.is_some());
+}
+
+#[test]
+fn test_missing_program_fails() {
+    let config = ProcessConfig {
+        command: "/nonexistent/transcriber".to_string(),
+        ..ProcessConfig::default()
+    };
+    assert!(ProcessTranscriber::new(&config).is_err());
+}
+
+#[test]
+fn test_frame_roundtrip() {
+    let mut buffer = Vec::new();
+    write_frame(&mut buffer, b"hello").unwrap();
+    assert_eq!(buffer[..4], [5, 0, 0, 0]);
+    assert_eq!(read_frame(&mut buffer.as_slice()).unwrap(), b"hello");
+}
+
+#[test]
+fn test_oversized_frame_rejected() {
+    let frame = u32::MAX.to_le_bytes();
+    let err = read_frame(&mut frame.as_slice()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,108 @@
+//! Transcriber answering from a script, for tests.
+//!
+//! Needs no model: each call returns the next scripted transcript, and every
+//! request is recorded so tests can check what the pipeline sent.
+
+use super::{Transcriber, Transcript, Vocabulary};
+use crate::config::Task;
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// What the pipeline asked a [`ScriptedTranscriber`] to transcribe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptedRequest {
+    /// Number of 16kHz samples.
+    pub samples: usize,
+    pub language: Option<String>,
+    pub task: Task,
+    /// Vocabulary prompt for `language`.
+    pub prompt: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Script {
+    transcripts: VecDeque<Transcript>,
+    requests: Vec<ScriptedRequest>,
+}
+
+/// Transcriber returning scripted transcripts in order, then empty ones.
+///
+/// Clones share the script, so a test can keep one to inspect requests
+/// while the engine owns another.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedTranscriber {
+    script: Arc<Mutex<Script>>,
+    language: Option<String>,
+    vocabulary: Vocabulary,
+    task: Task,
+}
+
+impl ScriptedTranscriber {
+    /// Answer with `texts`, one per transcription.
+    pub fn new<S: Into<String>>(texts: impl IntoIterator<Item = S>) -> Self {
+        Self::from_transcripts(texts.into_iter().map(Transcript::from_text))
+    }
+
+    /// Answer with `transcripts`, one per transcription.
+    pub fn from_transcripts(transcripts: impl IntoIterator<Item = Transcript>) -> Self {
+        let script = Script {
+            transcripts: transcripts.into_iter().collect(),
+            requests: Vec::new(),
+        };
+        Self {
+            script: Arc::new(Mutex::new(script)),
+            ..Self::default()
+        }
+    }
+
+    /// Requests received so far, by this transcriber and its clones.
+    pub fn requests(&self) -> Vec<ScriptedRequest> {
+        self.script
+            .lock()
+            .map(|script| script.requests.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Transcriber for ScriptedTranscriber {
+    fn transcribe(&mut self, audio: &[f32], sample_rate: u32) -> Result<Transcript> {
+        if sample_rate != 16000 {
+            anyhow::bail!("Expected 16kHz audio, got {sample_rate}Hz");
+        }
+        let mut script = self
+            .script
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to lock script: {e}"))?;
+        script.requests.push(ScriptedRequest {
+            samples: audio.len(),
+            language: self.language.clone(),
+            task: self.task,
+            prompt: self
+                .vocabulary
+                .prompt_for(self.language.as_deref())
+                .map(str::to_string),
+        });
+        let mut transcript = script.transcripts.pop_front().unwrap_or_default();
+        if transcript.language.is_none() {
+            transcript.language = self.language.clone();
+        }
+        Ok(transcript)
+    }
+
+    fn set_language(&mut self, language: Option<String>) {
+        self.language = language;
+    }
+
+    fn set_vocabulary(&mut self, vocabulary: Vocabulary) {
+        self.vocabulary = vocabulary;
+    }
+
+    fn set_task(&mut self, task: Task) {
+        self.task = task;
+    }
+}
+
+#[cfg(test)]
+#[path = "scripted_test.rs"]
+mod tests;
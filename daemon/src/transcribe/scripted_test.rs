@@ -0,0 +1,46 @@
+use super::*;
+
+#[test]
+fn test_answers_in_order_then_empty() {
+    let mut transcriber = ScriptedTranscriber::new(["one", "two"]);
+    assert_eq!(
+        transcriber.transcribe(&[0.0; 10], 16000).unwrap().text,
+        "one"
+    );
+    assert_eq!(
+        transcriber.transcribe(&[0.0; 10], 16000).unwrap().text,
+        "two"
+    );
+    assert_eq!(transcriber.transcribe(&[0.0; 10], 16000).unwrap().text, "");
+}
+
+#[test]
+fn test_clones_share_requests() {
+    let transcriber = ScriptedTranscriber::new(["hello"]);
+    let mut engine_side = transcriber.clone();
+    engine_side.set_language(Some("cs".to_string()));
+    engine_side.set_task(Task::Translate);
+    engine_side.set_vocabulary(Vocabulary {
+        prompt: Some("Praha.".to_string()),
+        ..Vocabulary::default()
+    });
+
+    let transcript = engine_side.transcribe(&[0.0; 1600], 16000).unwrap();
+    assert_eq!(transcript.language.as_deref(), Some("cs"));
+    assert_eq!(
+        transcriber.requests(),
+        vec![ScriptedRequest {
+            samples: 1600,
+            language: Some("cs".to_string()),
+            task: Task::Translate,
+            prompt: Some("Praha.".to_string()),
+        }]
+    );
+}
+
+#[test]
+fn test_rejects_other_sample_rates() {
+    let mut transcriber = ScriptedTranscriber::new(["hello"]);
+    assert!(transcriber.transcribe(&[0.0; 10], 48000).is_err());
+    assert!(transcriber.requests().is_empty());
+}
@@ -2,26 +2,49 @@
 //!
 //! Uses whisper.cpp via whisper-rs for speech-to-text.
 
-use super::Transcriber;
+use super::{
+    DecodingOptions, Transcriber, Transcript, TranscriptSegment, Vocabulary, language_code,
+};
+use crate::config::Task;
 use anyhow::{Context, Result};
+use std::ffi::{c_int, c_void};
 use std::path::Path;
 use tracing::{debug, info};
 use whisper_rs::{
     FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+    WhisperSysContext, WhisperSysState, WhisperTokenData, WhisperTokenId,
 };
 
-/// Segments with no-speech probability above this threshold are dropped.
-/// Whisper's default no_speech_thold is 0.6; we use the same value.
-const NO_SPEECH_PROB_THRESHOLD: f32 = 0.6;
+/// Most threads whisper.cpp uses by default.
+const MAX_DEFAULT_THREADS: usize = 4;
+
+/// Characters of the tokens suppressed when punctuation is spoken.
+/// Apostrophes and hyphens are left alone since they occur inside words.
+const PUNCTUATION: &[char] = &[
+    ',', '.', '!', '?', ':', ';', '…', '"', '“', '”', '„', '(', ')', '–', '—',
+];
+
+/// A detected language code and its probability.
+type Detection = (&'static str, f32);
 
 /// Whisper speech-to-text transcriber.
 ///
-/// The underlying WhisperContext is leaked intentionally - for a long-running daemon,
-/// the model stays loaded for the process lifetime. This avoids complex self-referential
-/// struct patterns while allowing the state to be reused across transcriptions.
+/// Owns the loaded model; its memory is released when the transcriber is
+/// dropped, so models can be reloaded or switched in a running daemon.
 pub struct WhisperTranscriber {
+    /// Decoding state, reused across transcriptions. Declared before `ctx` so
+    /// it is freed first.
     state: WhisperState,
+    /// The model. The state holds a reference-counted handle to it too.
+    ctx: WhisperContext,
     language: Option<String>,
+    /// Codes auto-detection may choose from; empty allows any.
+    candidate_languages: Vec<String>,
+    vocabulary: Vocabulary,
+    task: Task,
+    decoding: DecodingOptions,
+    /// Text tokens consisting only of punctuation.
+    punctuation_tokens: Vec<WhisperTokenId>,
 }
 
 impl WhisperTranscriber {
@@ -43,24 +66,128 @@ impl WhisperTranscriber {
         )
         .context("Failed to load Whisper model")?;
 
-        // Box and leak the context to get a 'static reference.
-        // This is intentional for a long-running daemon - the model stays loaded for the process lifetime.
-        let ctx_box = Box::new(ctx);
-        let ctx_ref: &'static WhisperContext = Box::leak(ctx_box);
-
-        let state = ctx_ref
+        let state = ctx
             .create_state()
             .context("Failed to create Whisper state")?;
 
         info!("Whisper model and state loaded successfully");
 
-        Ok(Self { state, language })
+        let punctuation_tokens = (0..ctx.token_eot())
+            .filter(|&id| {
+                ctx.token_to_str_lossy(id)
+                    .is_ok_and(|text| is_punctuation(&text))
+            })
+            .collect();
+
+        Ok(Self {
+            state,
+            language,
+            candidate_languages: Vec::new(),
+            vocabulary: Vocabulary::default(),
+            task: Task::default(),
+            decoding: DecodingOptions::default(),
+            punctuation_tokens,
+            ctx,
+        })
     }
 
     /// Get the configured language.
     pub fn language(&self) -> Option<&str> {
         self.language.as_deref()
     }
+
+    /// Detect the spoken language, restricted to the candidate languages if
+    /// any. Returns the language code and its probability.
+    fn detect_language(&mut self, audio: &[f32]) -> Result<Detection> {
+        let threads = self.threads();
+        self.state
+            .pcm_to_mel(audio, threads)
+            .context("Failed to compute mel spectrogram")?;
+        let (detected, probabilities) = self
+            .state
+            .lang_detect(0, threads)
+            .context("Language detection failed")?;
+
+        let best = self
+            .candidate_languages
+            .iter()
+            .filter_map(|code| whisper_rs::get_lang_id(code))
+            .filter_map(|id| Some((id, *probabilities.get(usize::try_from(id).ok()?)?)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        let (id, probability) = match best {
+            Some(best) => best,
+            None => (
+                detected,
+                probabilities.get(detected as usize).copied().unwrap_or(0.0),
+            ),
+        };
+        let code = whisper_rs::get_lang_str(id).context("Unknown detected language")?;
+        debug!(
+            language = code,
+            probability = probability,
+            "Detected language"
+        );
+        Ok((code, probability))
+    }
+
+    /// Language to decode in, and the detected language with its probability
+    /// if it was detected.
+    fn resolve_language(&mut self, audio: &[f32]) -> Result<(Option<String>, Option<Detection>)> {
+        // Detect the language ourselves rather than letting whisper.cpp do it,
+        // so it can be restricted to the candidates and its probability reported.
+        // Decoding with a known language skips whisper.cpp's own detection pass.
+        // English-only models have no language tokens to detect with.
+        if !self.ctx.is_multilingual() {
+            return Ok((Some("en".to_string()), None));
+        }
+        if let Some(language) = &self.language {
+            let code = language_code(language).map_or_else(|| language.clone(), str::to_string);
+            return Ok((Some(code), None));
+        }
+        let detected = self.detect_language(audio)?;
+        Ok((Some(detected.0.to_string()), Some(detected)))
+    }
+
+    /// Inference threads: the configured count, or whisper.cpp's default.
+    fn threads(&self) -> usize {
+        match self.decoding.threads {
+            Some(threads) => threads as usize,
+            None => std::thread::available_parallelism()
+                .map_or(1, |n| n.get())
+                .min(MAX_DEFAULT_THREADS),
+        }
+    }
+
+    /// Keep the model from writing punctuation when the user dictates it.
+    fn set_punctuation_filter(&self, params: &mut FullParams<'_, '_>) {
+        if !self.decoding.suppress_punctuation {
+            return;
+        }
+        // SAFETY: the callback only reads the token list, which outlives
+        // the inference call and is not modified during it.
+        unsafe {
+            params.set_filter_logits_callback(Some(suppress_tokens));
+            params.set_filter_logits_callback_user_data(
+                std::ptr::from_ref(&self.punctuation_tokens)
+                    .cast_mut()
+                    .cast(),
+            );
+        }
+    }
+
+    /// Decoding strategy for the configured beam size.
+    fn sampling_strategy(&self) -> SamplingStrategy {
+        if self.decoding.beam_size > 1 {
+            SamplingStrategy::BeamSearch {
+                beam_size: self.decoding.beam_size as i32,
+                patience: -1.0,
+            }
+        } else {
+            SamplingStrategy::Greedy {
+                best_of: self.decoding.best_of as i32,
+            }
+        }
+    }
 }
 
 impl Transcriber for WhisperTranscriber {
@@ -68,7 +195,23 @@ impl Transcriber for WhisperTranscriber {
         self.language = language;
     }
 
-    fn transcribe(&mut self, audio: &[f32], sample_rate: u32) -> Result<String> {
+    fn set_candidate_languages(&mut self, languages: Vec<String>) {
+        self.candidate_languages = languages;
+    }
+
+    fn set_vocabulary(&mut self, vocabulary: Vocabulary) {
+        self.vocabulary = vocabulary;
+    }
+
+    fn set_task(&mut self, task: Task) {
+        self.task = task;
+    }
+
+    fn set_decoding(&mut self, options: DecodingOptions) {
+        self.decoding = options;
+    }
+
+    fn transcribe(&mut self, audio: &[f32], sample_rate: u32) -> Result<Transcript> {
         debug!(
             samples = audio.len(),
             sample_rate = sample_rate,
@@ -84,13 +227,25 @@ impl Transcriber for WhisperTranscriber {
             );
         }
 
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        let (language, detected) = self.resolve_language(audio)?;
 
-        // Configure language
-        if let Some(ref lang) = self.language {
-            params.set_language(Some(lang));
-        } else {
-            params.set_language(None); // Auto-detect
+        let mut params = FullParams::new(self.sampling_strategy());
+        params.set_language(language.as_deref());
+        params.set_translate(self.task == Task::Translate);
+        params.set_n_threads(self.threads() as i32);
+        params.set_temperature(self.decoding.temperature);
+        params.set_temperature_inc(self.decoding.temperature_increment);
+        params.set_no_speech_thold(self.decoding.no_speech_threshold);
+        params.set_entropy_thold(self.decoding.entropy_threshold);
+        params.set_logprob_thold(self.decoding.logprob_threshold);
+
+        // Bias spelling toward known terms; the prompt depends on the language
+        let prompt = self
+            .vocabulary
+            .prompt_for(language.as_deref())
+            .map(|prompt| prompt.replace('\0', ""));
+        if let Some(ref prompt) = prompt {
+            params.set_initial_prompt(prompt);
         }
 
         // Each VAD segment is independent — don't let the decoder use
@@ -99,7 +254,9 @@ impl Transcriber for WhisperTranscriber {
         params.set_no_context(true);
 
         // Suppress non-speech tokens (hallucinated filler like "[MUSIC]", etc.)
-        params.set_suppress_nst(true);
+        params.set_suppress_nst(self.decoding.suppress_non_speech);
+        params.set_suppress_blank(self.decoding.suppress_blank);
+        self.set_punctuation_filter(&mut params);
 
         // Disable printing to stdout
         params.set_print_special(false);
@@ -108,7 +265,7 @@ impl Transcriber for WhisperTranscriber {
         params.set_print_timestamps(false);
 
         // Single segment mode for lower latency
-        params.set_single_segment(true);
+        params.set_single_segment(self.decoding.single_segment);
 
         // Run inference using the pre-created state
         self.state
@@ -117,12 +274,17 @@ impl Transcriber for WhisperTranscriber {
 
         // Collect segments, filtering out those Whisper tags as non-speech.
         let num_segments = self.state.full_n_segments();
-        let mut result = String::new();
+        let mut transcript = Transcript::default();
+        let mut logprob_sum = 0.0;
+        let mut token_count = 0;
 
+        // Tokens from end of text on are special (language, timestamps)
+        let token_eot = self.ctx.token_eot();
         for i in 0..num_segments {
             if let Some(segment) = self.state.get_segment(i) {
                 let no_speech_prob = segment.no_speech_probability();
-                if no_speech_prob > NO_SPEECH_PROB_THRESHOLD {
+                transcript.no_speech_prob = transcript.no_speech_prob.max(no_speech_prob);
+                if no_speech_prob > self.decoding.no_speech_threshold {
                     debug!(
                         segment = i,
                         no_speech_prob = no_speech_prob,
@@ -130,18 +292,77 @@ impl Transcriber for WhisperTranscriber {
                     );
                     continue;
                 }
-                if let Ok(text) = segment.to_str_lossy() {
-                    result.push_str(&text);
+                let Ok(text) = segment.to_str_lossy() else {
+                    continue;
+                };
+
+                for t in 0..segment.n_tokens() {
+                    if let Some(token) = segment.get_token(t)
+                        && token.token_id() < token_eot
+                    {
+                        logprob_sum += token.token_data().plog;
+                        token_count += 1;
+                    }
                 }
+
+                transcript.text.push_str(&text);
+                transcript.segments.push(TranscriptSegment {
+                    start_ms: centiseconds_to_ms(segment.start_timestamp()),
+                    end_ms: centiseconds_to_ms(segment.end_timestamp()),
+                    text: text.trim().to_string(),
+                });
             }
         }
 
-        debug!(text_len = result.len(), "Transcription complete");
+        transcript.text = transcript.text.trim().to_string();
+        if token_count > 0 {
+            transcript.avg_logprob = logprob_sum / token_count as f32;
+        }
+        transcript.language = language;
+        transcript.language_probability = detected.map(|(_, probability)| probability);
+
+        debug!(
+            text_len = transcript.text.len(),
+            avg_logprob = transcript.avg_logprob,
+            language = ?transcript.language,
+            "Transcription complete"
+        );
+
+        Ok(transcript)
+    }
+}
+
+/// Whether a token's text is nothing but punctuation.
+fn is_punctuation(text: &str) -> bool {
+    let text = text.trim();
+    !text.is_empty() && text.chars().all(|c| PUNCTUATION.contains(&c))
+}
 
-        Ok(result.trim().to_string())
+/// Logits filter that rules out the tokens in the `Vec<WhisperTokenId>`
+/// passed as user data.
+unsafe extern "C" fn suppress_tokens(
+    _ctx: *mut WhisperSysContext,
+    _state: *mut WhisperSysState,
+    _tokens: *const WhisperTokenData,
+    _n_tokens: c_int,
+    logits: *mut f32,
+    user_data: *mut c_void,
+) {
+    // SAFETY: user data is the token list set alongside this callback, and
+    // every token is below end of text, so within the vocabulary's logits.
+    unsafe {
+        let tokens = &*user_data.cast::<Vec<WhisperTokenId>>();
+        for &token in tokens {
+            *logits.add(token as usize) = f32::NEG_INFINITY;
+        }
     }
 }
 
+/// Convert a Whisper timestamp (10 ms units) to milliseconds.
+fn centiseconds_to_ms(timestamp: i64) -> u32 {
+    (timestamp.max(0) * 10).try_into().unwrap_or(u32::MAX)
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -151,4 +372,22 @@ mod tests {
         let lang = Some("en".to_string());
         assert_eq!(lang.as_deref(), Some("en"));
     }
+
+    #[test]
+    fn test_centiseconds_to_ms() {
+        assert_eq!(super::centiseconds_to_ms(0), 0);
+        assert_eq!(super::centiseconds_to_ms(150), 1500);
+        assert_eq!(super::centiseconds_to_ms(-1), 0);
+    }
+
+    #[test]
+    fn test_is_punctuation() {
+        assert!(super::is_punctuation(","));
+        assert!(super::is_punctuation(" ..."));
+        assert!(super::is_punctuation("?\""));
+        assert!(!super::is_punctuation("'s"));
+        assert!(!super::is_punctuation("-"));
+        assert!(!super::is_punctuation(" period"));
+        assert!(!super::is_punctuation(" "));
+    }
 }
@@ -0,0 +1,308 @@
+//! Background transcription worker.
+//!
+//! Inference takes from tens of milliseconds to several seconds. Running it on
+//! a dedicated thread keeps audio capture and VAD going in the meantime;
+//! segments that finish during inference wait in a bounded queue. Nothing
+//! here blocks the caller: final segments that do not fit wait in an
+//! overflow list until the worker catches up, partials are turned away. The
+//! overflow holds at most [`MAX_HELD_SECS`] of audio; when the model falls
+//! further behind, the oldest held segments are dropped rather than typed
+//! minutes late.
+
+use super::{DecodingOptions, Transcriber, Transcript, Vocabulary};
+use crate::config::Task;
+use crate::vad::VAD_SAMPLE_RATE;
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::thread::JoinHandle;
+use tracing::{debug, warn};
+
+/// Most audio, in seconds, held back while the queue is full.
+pub const MAX_HELD_SECS: f32 = 60.0;
+
+/// What a job's result is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    /// Re-transcription of the speech segment still in progress.
+    Partial,
+    /// A finished speech segment.
+    Final,
+}
+
+/// Audio to transcribe, with the settings current when it was queued.
+#[derive(Debug)]
+pub struct TranscriptionJob {
+    pub kind: JobKind,
+    /// Mono samples at 16kHz. Empty jobs produce an empty transcript without
+    /// running the model, so a final result can be reported in queue order.
+    pub samples: Vec<f32>,
+    pub language: Option<String>,
+    /// Languages auto-detection may choose from; empty allows any.
+    pub candidate_languages: Vec<String>,
+    pub vocabulary: Vocabulary,
+    pub task: Task,
+    pub decoding: DecodingOptions,
+}
+
+/// Result of one job.
+#[derive(Debug)]
+pub struct JobOutput {
+    pub kind: JobKind,
+    pub transcript: Result<Transcript>,
+    /// Duration of the transcribed audio in seconds.
+    pub audio_secs: f32,
+}
+
+/// Held segments dropped because the worker fell too far behind.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DroppedSegments {
+    pub count: usize,
+    /// Duration of the dropped audio in seconds.
+    pub audio_secs: f32,
+}
+
+/// What the worker thread receives, in queue order.
+enum WorkerMessage {
+    Job(TranscriptionJob),
+    /// Use a different transcriber for the jobs queued after this.
+    Replace(Box<dyn Transcriber>),
+}
+
+/// Runs a [`Transcriber`] on its own thread, processing jobs in order.
+pub struct TranscriptionWorker {
+    jobs: Option<SyncSender<WorkerMessage>>,
+    /// Messages waiting for room in the queue, oldest first.
+    overflow: VecDeque<WorkerMessage>,
+    /// Segments dropped from the overflow since last taken.
+    dropped: DroppedSegments,
+    results: Receiver<JobOutput>,
+    /// Jobs queued or running whose output has not been received yet.
+    pending: usize,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TranscriptionWorker {
+    /// Start a worker thread owning `transcriber`, queueing up to `capacity`
+    /// jobs while one is running.
+    pub fn spawn(transcriber: Box<dyn Transcriber>, capacity: usize) -> Result<Self> {
+        let (jobs_tx, jobs_rx) = mpsc::sync_channel(capacity);
+        let (results_tx, results_rx) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("vcm-transcribe".to_string())
+            .spawn(move || run_worker(transcriber, jobs_rx, results_tx))
+            .context("Failed to spawn transcription worker")?;
+
+        Ok(Self {
+            jobs: Some(jobs_tx),
+            overflow: VecDeque::new(),
+            dropped: DroppedSegments::default(),
+            results: results_rx,
+            pending: 0,
+            thread: Some(thread),
+        })
+    }
+
+    /// Queue a job without waiting. Returns the job back if the queue is full
+    /// or held segments are waiting ahead of it.
+    pub fn try_submit(&mut self, job: TranscriptionJob) -> Result<(), Box<TranscriptionJob>> {
+        self.flush_overflow();
+        let Some(jobs) = self.jobs.as_ref().filter(|_| self.overflow.is_empty()) else {
+            return Err(Box::new(job));
+        };
+        match jobs.try_send(WorkerMessage::Job(job)) {
+            Ok(()) => {
+                self.pending += 1;
+                Ok(())
+            }
+            Err(TrySendError::Full(message) | TrySendError::Disconnected(message)) => match message
+            {
+                WorkerMessage::Job(job) => Err(Box::new(job)),
+                WorkerMessage::Replace(_) => unreachable!("sent a job"),
+            },
+        }
+    }
+
+    /// Queue a job without waiting.
+    ///
+    /// A final job that does not fit is kept until the worker makes room,
+    /// dropping the oldest held ones beyond [`MAX_HELD_SECS`] of audio.
+    /// A partial one is dropped: a newer partial or the final result
+    /// replaces it anyway.
+    pub fn submit(&mut self, job: TranscriptionJob) -> Result<()> {
+        let job = match self.try_submit(job) {
+            Ok(()) => return Ok(()),
+            Err(job) => job,
+        };
+        if job.kind == JobKind::Partial {
+            warn!("Transcription queue full, dropping partial transcription");
+            return Ok(());
+        }
+        self.jobs.as_ref().context("Transcription worker stopped")?;
+        warn!("Transcription queue full, holding segment until the worker catches up");
+        self.pending += 1;
+        self.overflow.push_back(WorkerMessage::Job(*job));
+        self.limit_overflow();
+        Ok(())
+    }
+
+    /// Drop the oldest held jobs, but never the newest, until the held
+    /// audio fits in [`MAX_HELD_SECS`].
+    fn limit_overflow(&mut self) {
+        let held_secs = |overflow: &VecDeque<WorkerMessage>| -> f32 {
+            overflow
+                .iter()
+                .filter_map(|message| match message {
+                    WorkerMessage::Job(job) => Some(audio_secs(job)),
+                    WorkerMessage::Replace(_) => None,
+                })
+                .sum()
+        };
+        while held_secs(&self.overflow) > MAX_HELD_SECS {
+            let mut jobs = self
+                .overflow
+                .iter()
+                .enumerate()
+                .filter(|(_, message)| matches!(message, WorkerMessage::Job(_)))
+                .map(|(index, _)| index);
+            let (Some(oldest), Some(_newer)) = (jobs.next(), jobs.next()) else {
+                return;
+            };
+            let Some(WorkerMessage::Job(job)) = self.overflow.remove(oldest) else {
+                unreachable!("found a job");
+            };
+            let secs = audio_secs(&job);
+            warn!(
+                audio_secs = secs,
+                "Transcription fell behind, dropping held speech segment"
+            );
+            self.pending -= 1;
+            self.dropped.count += 1;
+            self.dropped.audio_secs += secs;
+        }
+    }
+
+    /// Segments dropped because the worker fell behind since last called.
+    pub fn take_dropped(&mut self) -> Option<DroppedSegments> {
+        (self.dropped.count > 0).then(|| std::mem::take(&mut self.dropped))
+    }
+
+    /// Switch to a different transcriber.
+    ///
+    /// Jobs already queued finish on the current one; the old transcriber is
+    /// dropped once they are done.
+    pub fn replace_transcriber(&mut self, transcriber: Box<dyn Transcriber>) -> Result<()> {
+        self.jobs.as_ref().context("Transcription worker stopped")?;
+        self.overflow.push_back(WorkerMessage::Replace(transcriber));
+        self.flush_overflow();
+        Ok(())
+    }
+
+    /// Move waiting messages into the queue while there is room.
+    fn flush_overflow(&mut self) {
+        let Some(jobs) = &self.jobs else {
+            return;
+        };
+        while let Some(message) = self.overflow.pop_front() {
+            match jobs.try_send(message) {
+                Ok(()) => {}
+                Err(TrySendError::Full(message)) => {
+                    self.overflow.push_front(message);
+                    return;
+                }
+                Err(TrySendError::Disconnected(message)) => {
+                    warn!("Transcription worker stopped, dropping queued segments");
+                    let dropped = std::iter::once(message)
+                        .chain(self.overflow.drain(..))
+                        .filter(|message| matches!(message, WorkerMessage::Job(_)))
+                        .count();
+                    self.pending -= dropped;
+                    self.jobs = None;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Take the next finished result, if any.
+    pub fn try_recv(&mut self) -> Option<JobOutput> {
+        self.flush_overflow();
+        let output = self.results.try_recv().ok()?;
+        self.pending -= 1;
+        Some(output)
+    }
+
+    /// Number of jobs queued or running.
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Whether the worker has nothing queued or running.
+    pub fn is_idle(&self) -> bool {
+        self.pending == 0
+    }
+}
+
+impl Drop for TranscriptionWorker {
+    fn drop(&mut self) {
+        // Closing the queue ends the worker loop once the current job is done
+        self.jobs = None;
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            warn!("Transcription worker panicked");
+        }
+    }
+}
+
+fn run_worker(
+    mut transcriber: Box<dyn Transcriber>,
+    jobs: Receiver<WorkerMessage>,
+    results: mpsc::Sender<JobOutput>,
+) {
+    debug!("Transcription worker started");
+    for message in jobs {
+        let job = match message {
+            WorkerMessage::Job(job) => job,
+            WorkerMessage::Replace(replacement) => {
+                debug!("Replacing transcriber");
+                transcriber = replacement;
+                continue;
+            }
+        };
+        let audio_secs = audio_secs(&job);
+        let transcript = if job.samples.is_empty() {
+            Ok(Transcript::default())
+        } else {
+            transcriber.set_language(job.language);
+            transcriber.set_candidate_languages(job.candidate_languages);
+            transcriber.set_vocabulary(job.vocabulary);
+            transcriber.set_task(job.task);
+            transcriber.set_decoding(job.decoding);
+            transcriber
+                .transcribe(&job.samples, VAD_SAMPLE_RATE)
+                .map(|transcript| Transcript {
+                    translated: job.task == Task::Translate,
+                    ..transcript
+                })
+        };
+        let output = JobOutput {
+            kind: job.kind,
+            transcript,
+            audio_secs,
+        };
+        if results.send(output).is_err() {
+            break;
+        }
+    }
+    debug!("Transcription worker stopped");
+}
+
+/// Duration of the job's audio in seconds.
+fn audio_secs(job: &TranscriptionJob) -> f32 {
+    job.samples.len() as f32 / VAD_SAMPLE_RATE as f32
+}
+
+#[cfg(test)]
+#[path = "worker_test.rs"]
+mod tests;
@@ -0,0 +1,262 @@
+use super::*;
+use std::sync::{Arc, Barrier};
+use std::time::{Duration, Instant};
+
+/// Echoes the job settings and sample count, optionally waiting on a barrier first.
+struct EchoTranscriber {
+    name: &'static str,
+    language: Option<String>,
+    beam_size: u32,
+    gate: Option<Arc<Barrier>>,
+}
+
+impl EchoTranscriber {
+    fn new() -> Self {
+        Self {
+            name: "echo",
+            language: None,
+            beam_size: 1,
+            gate: None,
+        }
+    }
+}
+
+impl Transcriber for EchoTranscriber {
+    fn transcribe(&mut self, audio: &[f32], _sample_rate: u32) -> Result<Transcript> {
+        if let Some(gate) = &self.gate {
+            gate.wait();
+        }
+        Ok(Transcript::from_text(format!(
+            "{} {} {:?} {}",
+            self.name,
+            audio.len(),
+            self.language,
+            self.beam_size
+        )))
+    }
+
+    fn set_language(&mut self, language: Option<String>) {
+        self.language = language;
+    }
+
+    fn set_decoding(&mut self, options: DecodingOptions) {
+        self.beam_size = options.beam_size;
+    }
+}
+
+fn job(kind: JobKind, len: usize) -> TranscriptionJob {
+    TranscriptionJob {
+        kind,
+        samples: vec![0.0; len],
+        language: None,
+        candidate_languages: Vec::new(),
+        vocabulary: Vocabulary::default(),
+        task: Task::Transcribe,
+        decoding: DecodingOptions::default(),
+    }
+}
+
+/// Text of a successful result.
+fn text(output: JobOutput) -> String {
+    output.transcript.unwrap().text
+}
+
+/// Wait for the next result, failing the test after a few seconds.
+fn recv(worker: &mut TranscriptionWorker) -> JobOutput {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        if let Some(output) = worker.try_recv() {
+            return output;
+        }
+        assert!(Instant::now() < deadline, "timed out waiting for result");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn test_results_arrive_in_submission_order() {
+    let mut worker = TranscriptionWorker::spawn(Box::new(EchoTranscriber::new()), 4).unwrap();
+    worker.submit(job(JobKind::Partial, 1)).unwrap();
+    worker.submit(job(JobKind::Final, 2)).unwrap();
+    assert_eq!(worker.pending(), 2);
+
+    let first = recv(&mut worker);
+    assert_eq!(first.kind, JobKind::Partial);
+    assert_eq!(text(first), "echo 1 None 1");
+    let second = recv(&mut worker);
+    assert_eq!(second.kind, JobKind::Final);
+    assert_eq!(text(second), "echo 2 None 1");
+    assert!(worker.is_idle());
+}
+
+#[test]
+fn test_job_settings_are_applied() {
+    let mut worker = TranscriptionWorker::spawn(Box::new(EchoTranscriber::new()), 1).unwrap();
+    worker
+        .submit(TranscriptionJob {
+            kind: JobKind::Final,
+            samples: vec![0.0; 3],
+            language: Some("cs".to_string()),
+            candidate_languages: Vec::new(),
+            vocabulary: Vocabulary::default(),
+            task: Task::Transcribe,
+            decoding: DecodingOptions {
+                beam_size: 5,
+                ..DecodingOptions::default()
+            },
+        })
+        .unwrap();
+    assert_eq!(text(recv(&mut worker)), "echo 3 Some(\"cs\") 5");
+}
+
+#[test]
+fn test_translations_marked() {
+    let mut worker = TranscriptionWorker::spawn(Box::new(EchoTranscriber::new()), 1).unwrap();
+    worker
+        .submit(TranscriptionJob {
+            task: Task::Translate,
+            ..job(JobKind::Final, 3)
+        })
+        .unwrap();
+    let output = recv(&mut worker);
+    assert!(output.transcript.unwrap().translated);
+}
+
+#[test]
+fn test_empty_job_skips_transcriber() {
+    let mut worker = TranscriptionWorker::spawn(Box::new(EchoTranscriber::new()), 1).unwrap();
+    worker.submit(job(JobKind::Final, 0)).unwrap();
+    assert_eq!(text(recv(&mut worker)), "");
+}
+
+#[test]
+fn test_try_submit_returns_job_when_queue_full() {
+    let gate = Arc::new(Barrier::new(2));
+    let transcriber = EchoTranscriber {
+        gate: Some(Arc::clone(&gate)),
+        ..EchoTranscriber::new()
+    };
+    let mut worker = TranscriptionWorker::spawn(Box::new(transcriber), 1).unwrap();
+
+    // The first job blocks in the transcriber, the second fills the queue
+    worker.try_submit(job(JobKind::Final, 1)).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while worker.try_submit(job(JobKind::Final, 2)).is_err() {
+        assert!(Instant::now() < deadline, "worker never took the first job");
+        std::thread::yield_now();
+    }
+    let rejected = worker.try_submit(job(JobKind::Partial, 3)).unwrap_err();
+    assert_eq!(rejected.kind, JobKind::Partial);
+    assert_eq!(worker.pending(), 2);
+
+    gate.wait();
+    gate.wait();
+    assert_eq!(text(recv(&mut worker)), "echo 1 None 1");
+    assert_eq!(text(recv(&mut worker)), "echo 2 None 1");
+}
+
+#[test]
+fn test_replaced_transcriber_takes_later_jobs() {
+    let mut worker = TranscriptionWorker::spawn(Box::new(EchoTranscriber::new()), 4).unwrap();
+    worker.submit(job(JobKind::Final, 1)).unwrap();
+    worker
+        .replace_transcriber(Box::new(EchoTranscriber {
+            name: "new",
+            ..EchoTranscriber::new()
+        }))
+        .unwrap();
+    worker.submit(job(JobKind::Final, 2)).unwrap();
+
+    assert_eq!(text(recv(&mut worker)), "echo 1 None 1");
+    assert_eq!(text(recv(&mut worker)), "new 2 None 1");
+    assert!(worker.is_idle());
+}
+
+#[test]
+fn test_submit_holds_finals_when_queue_full() {
+    let gate = Arc::new(Barrier::new(2));
+    let transcriber = EchoTranscriber {
+        gate: Some(Arc::clone(&gate)),
+        ..EchoTranscriber::new()
+    };
+    let mut worker = TranscriptionWorker::spawn(Box::new(transcriber), 1).unwrap();
+
+    // The first job blocks in the transcriber; none of these calls may wait
+    for len in 1..=4 {
+        worker.submit(job(JobKind::Final, len)).unwrap();
+    }
+    worker
+        .replace_transcriber(Box::new(EchoTranscriber {
+            name: "new",
+            ..EchoTranscriber::new()
+        }))
+        .unwrap();
+    worker.submit(job(JobKind::Final, 5)).unwrap();
+    assert_eq!(worker.pending(), 5);
+
+    for len in 1..=4 {
+        gate.wait();
+        assert_eq!(text(recv(&mut worker)), format!("echo {len} None 1"));
+    }
+    assert_eq!(text(recv(&mut worker)), "new 5 None 1");
+    assert!(worker.is_idle());
+}
+
+#[test]
+fn test_submit_drops_partial_when_queue_full() {
+    let gate = Arc::new(Barrier::new(2));
+    let transcriber = EchoTranscriber {
+        gate: Some(Arc::clone(&gate)),
+        ..EchoTranscriber::new()
+    };
+    let mut worker = TranscriptionWorker::spawn(Box::new(transcriber), 1).unwrap();
+
+    worker.submit(job(JobKind::Final, 1)).unwrap();
+    worker.submit(job(JobKind::Final, 2)).unwrap();
+    worker.submit(job(JobKind::Partial, 3)).unwrap();
+    assert_eq!(worker.pending(), 2);
+
+    for len in 1..=2 {
+        gate.wait();
+        assert_eq!(text(recv(&mut worker)), format!("echo {len} None 1"));
+    }
+    assert!(worker.is_idle());
+}
+
+#[test]
+fn test_submit_drops_oldest_held_finals_beyond_limit() {
+    let gate = Arc::new(Barrier::new(2));
+    let transcriber = EchoTranscriber {
+        gate: Some(Arc::clone(&gate)),
+        ..EchoTranscriber::new()
+    };
+    let mut worker = TranscriptionWorker::spawn(Box::new(transcriber), 1).unwrap();
+    let secs = |secs: usize| secs * VAD_SAMPLE_RATE as usize;
+
+    worker.submit(job(JobKind::Final, 1)).unwrap();
+    // Once the worker has taken the first job, the second fills the queue
+    let mut queued = job(JobKind::Final, 2);
+    while let Err(job) = worker.try_submit(queued) {
+        queued = *job;
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    // Held: 25 s, then 50 s, then 75 s, which drops the oldest
+    for len in [secs(25), secs(25) + 1, secs(25) + 2] {
+        worker.submit(job(JobKind::Final, len)).unwrap();
+    }
+    assert_eq!(worker.pending(), 4);
+    assert_eq!(
+        worker.take_dropped(),
+        Some(DroppedSegments {
+            count: 1,
+            audio_secs: 25.0,
+        })
+    );
+    assert_eq!(worker.take_dropped(), None);
+
+    for len in [1, 2, secs(25) + 1, secs(25) + 2] {
+        gate.wait();
+        assert_eq!(text(recv(&mut worker)), format!("echo {len} None 1"));
+    }
+    assert!(worker.is_idle());
+}
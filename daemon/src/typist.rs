@@ -0,0 +1,229 @@
+//! Typing consecutive utterances as continuous text.
+//!
+//! Each utterance is transcribed on its own, so the model starts every one
+//! as a new sentence with no space before it. The typist remembers how the
+//! text it last typed into the frontmost app ended and joins the next
+//! utterance onto it: with a separating space, and capitalized after a
+//! sentence end. Capitals elsewhere are kept, since names and "I" can't be
+//! told apart from a capital the model put at the start. It also remembers
+//! how long recent utterances were, so they can be deleted again.
+
+use crate::inject::TextInjector;
+use crate::postprocess::{SENTENCE_END, capitalize};
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// Characters after which the next word follows without a space. Straight
+/// quotes are left out: at the end of an utterance they usually close.
+const OPENING: &[char] = &['(', '[', '{', '„', '-', '/'];
+
+/// Punctuation that follows a word without a space.
+const CLOSING: &[char] = &[',', '.', '!', '?', ':', ';', '…', ')', ']', '}'];
+
+/// Utterances remembered for undo.
+const HISTORY_LEN: usize = 20;
+
+/// How the last typed text ended.
+#[derive(Debug, Clone, PartialEq)]
+struct LastTyped {
+    /// Frontmost app at the time, if known.
+    app: Option<String>,
+    at: Instant,
+    last_char: char,
+}
+
+/// One utterance as typed.
+#[derive(Debug, Clone, PartialEq)]
+struct Typed {
+    /// Frontmost app it was typed into, if known.
+    app: Option<String>,
+    /// Characters typed, each deleted by one backspace.
+    chars: usize,
+    /// How the text before it ended, restored when it is undone.
+    before: Option<LastTyped>,
+}
+
+/// What was typed, kept across listening sessions.
+#[derive(Debug, Default)]
+pub struct TypingState {
+    last: Option<LastTyped>,
+    /// The utterance being typed.
+    current: Option<Typed>,
+    /// Finished utterances, oldest first.
+    history: VecDeque<Typed>,
+}
+
+impl TypingState {
+    /// Move the utterance being typed to the history.
+    fn finish(&mut self) -> bool {
+        let Some(typed) = self.current.take() else {
+            return false;
+        };
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(typed);
+        true
+    }
+}
+
+/// Typing state shared between the listening session and undo requests.
+pub type SharedTypingState = Arc<Mutex<TypingState>>;
+
+/// Types text through an injector, continuing what it typed before.
+pub struct Typist {
+    injector: Box<dyn TextInjector>,
+    /// Context older than this is forgotten; zero disables joining.
+    reset_after: Duration,
+    state: SharedTypingState,
+}
+
+impl Typist {
+    pub fn new(
+        injector: Box<dyn TextInjector>,
+        reset_after: Duration,
+        state: SharedTypingState,
+    ) -> Self {
+        Self {
+            injector,
+            reset_after,
+            state,
+        }
+    }
+
+    /// Type `text` into the frontmost app.
+    ///
+    /// Text starting with whitespace continues the utterance in progress
+    /// and is typed as is. Text the injector declines to type is forgotten.
+    pub fn type_text(&mut self, text: &str) -> Result<()> {
+        self.type_text_in(text, frontmost_app(), Instant::now())
+    }
+
+    fn type_text_in(&mut self, text: &str, app: Option<String>, now: Instant) -> Result<()> {
+        let Some(last_char) = text.chars().last() else {
+            return Ok(());
+        };
+        let mut state = lock(&self.state)?;
+        let text = match context(&state, app.as_deref(), now, self.reset_after) {
+            Some(previous) => join(previous, text),
+            None => text.to_string(),
+        };
+        if !self.injector.inject_text(&text)? {
+            debug!("Text was not typed, typing context kept");
+            return Ok(());
+        }
+
+        if state
+            .current
+            .as_ref()
+            .is_some_and(|current| current.app != app)
+        {
+            state.finish();
+        }
+        let before = state.last.clone();
+        let current = state.current.get_or_insert(Typed {
+            app: app.clone(),
+            chars: 0,
+            before,
+        });
+        current.chars += text.chars().count();
+        state.last = Some(LastTyped {
+            app,
+            at: now,
+            last_char,
+        });
+        Ok(())
+    }
+
+    /// Mark the utterance as complete. Returns whether any of it was typed.
+    pub fn finish_utterance(&mut self) -> Result<bool> {
+        Ok(lock(&self.state)?.finish())
+    }
+
+    /// Delete the most recent utterance from the frontmost app, returning
+    /// how many characters were deleted.
+    ///
+    /// Refuses when the utterance was typed into another app.
+    pub fn undo_last(&mut self) -> Result<usize> {
+        self.undo_last_in(frontmost_app())
+    }
+
+    fn undo_last_in(&mut self, app: Option<String>) -> Result<usize> {
+        let mut state = lock(&self.state)?;
+        state.finish();
+        let Some(typed) = state.history.back() else {
+            anyhow::bail!("Nothing to undo");
+        };
+        if typed.app != app {
+            anyhow::bail!(
+                "Frontmost app changed since the text was typed into {}",
+                typed.app.as_deref().unwrap_or("another app")
+            );
+        }
+        self.injector.delete_backward(typed.chars)?;
+
+        let chars = typed.chars;
+        let before = typed.before.clone();
+        state.history.pop_back();
+        state.last = before;
+        info!(chars = chars, "Undid last utterance");
+        Ok(chars)
+    }
+}
+
+fn lock(state: &SharedTypingState) -> Result<MutexGuard<'_, TypingState>> {
+    state
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock typing state: {e}"))
+}
+
+/// Last character typed into `app`, unless that was `reset_after` or
+/// longer ago.
+fn context(
+    state: &TypingState,
+    app: Option<&str>,
+    now: Instant,
+    reset_after: Duration,
+) -> Option<char> {
+    let last = state.last.as_ref()?;
+    if last.app.as_deref() != app {
+        debug!("Frontmost app changed, typing context reset");
+        return None;
+    }
+    (now.duration_since(last.at) < reset_after).then_some(last.last_char)
+}
+
+/// Name of the frontmost app, if the platform can tell.
+fn frontmost_app() -> Option<String> {
+    vcm_platform::frontmost::current()
+        .inspect_err(|e| debug!(error = %e, "Failed to get frontmost app for typing context"))
+        .ok()
+}
+
+/// Adjust `text` to follow `previous`, the last character typed.
+fn join(previous: char, text: &str) -> String {
+    let Some(first) = text.chars().next() else {
+        return String::new();
+    };
+    if first.is_whitespace() {
+        return text.to_string();
+    }
+
+    let (word, rest) = text.split_at(text.find(char::is_whitespace).unwrap_or(text.len()));
+    let word = if previous == '\n' || SENTENCE_END.contains(&previous) {
+        capitalize(word)
+    } else {
+        word.to_string()
+    };
+
+    let space =
+        !previous.is_whitespace() && !OPENING.contains(&previous) && !CLOSING.contains(&first);
+    format!("{}{word}{rest}", if space { " " } else { "" })
+}
+
+#[cfg(test)]
+#[path = "typist_test.rs"]
+mod tests;
@@ -0,0 +1,242 @@
+use super::*;
+use crate::inject::RecordingInjector;
+
+const RESET: Duration = Duration::from_secs(30);
+
+fn typist(recorder: &RecordingInjector, reset_after: Duration) -> Typist {
+    Typist::new(
+        Box::new(recorder.clone()),
+        reset_after,
+        SharedTypingState::default(),
+    )
+}
+
+fn app(name: &str) -> Option<String> {
+    Some(name.to_string())
+}
+
+#[test]
+fn test_utterances_joined_with_space() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, RESET);
+    let start = Instant::now();
+
+    typist
+        .type_text_in("Hello world.", app("Notes"), start)
+        .unwrap();
+    typist
+        .type_text_in("How are you", app("Notes"), start)
+        .unwrap();
+    typist.type_text_in("today?", app("Notes"), start).unwrap();
+
+    assert_eq!(
+        recorder.texts(),
+        vec!["Hello world.", " How are you", " today?"]
+    );
+}
+
+#[test]
+fn test_sentence_start_capitalized() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, RESET);
+    let start = Instant::now();
+
+    typist.type_text_in("Done!", None, start).unwrap();
+    typist.type_text_in("next one,", None, start).unwrap();
+    typist.type_text_in("I think", None, start).unwrap();
+
+    assert_eq!(recorder.texts(), vec!["Done!", " Next one,", " I think"]);
+}
+
+#[test]
+fn test_no_space_after_line_break_or_before_punctuation() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, RESET);
+    let start = Instant::now();
+
+    typist.type_text_in("Dear Anna,\n", None, start).unwrap();
+    typist.type_text_in("thanks", None, start).unwrap();
+    typist.type_text_in(", really", None, start).unwrap();
+    // Continuations of the utterance in progress are typed as is
+    typist.type_text_in(" Much", None, start).unwrap();
+
+    assert_eq!(
+        recorder.texts(),
+        vec!["Dear Anna,\n", "Thanks", ", really", " Much"]
+    );
+}
+
+#[test]
+fn test_name_keeps_capital() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, RESET);
+    let start = Instant::now();
+
+    typist
+        .type_text_in("Last year we went to", None, start)
+        .unwrap();
+    typist.type_text_in("Paris is nice.", None, start).unwrap();
+
+    assert_eq!(
+        recorder.texts(),
+        vec!["Last year we went to", " Paris is nice."]
+    );
+}
+
+#[test]
+fn test_i_keeps_capital() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, RESET);
+    let start = Instant::now();
+
+    typist.type_text_in("So yesterday,", None, start).unwrap();
+    typist.type_text_in("I left early", None, start).unwrap();
+
+    assert_eq!(recorder.texts(), vec!["So yesterday,", " I left early"]);
+}
+
+#[test]
+fn test_german_nouns_keep_capitals() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, RESET);
+    let start = Instant::now();
+
+    typist.type_text_in("Ich kaufe", None, start).unwrap();
+    typist.type_text_in("Brot.", None, start).unwrap();
+
+    assert_eq!(recorder.texts(), vec!["Ich kaufe", " Brot."]);
+}
+
+#[test]
+fn test_context_reset_by_app_change_and_idle_gap() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, RESET);
+    let start = Instant::now();
+
+    typist
+        .type_text_in("In the editor", app("Editor"), start)
+        .unwrap();
+    typist
+        .type_text_in("Chat message", app("Chat"), start)
+        .unwrap();
+    typist
+        .type_text_in("Much later", app("Chat"), start + RESET)
+        .unwrap();
+
+    assert_eq!(
+        recorder.texts(),
+        vec!["In the editor", "Chat message", "Much later"]
+    );
+}
+
+#[test]
+fn test_declined_text_leaves_context() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, RESET);
+    let start = Instant::now();
+
+    recorder.set_declining(true);
+    typist
+        .type_text_in("Outside the allowlist", None, start)
+        .unwrap();
+    recorder.set_declining(false);
+    typist.type_text_in("First line", None, start).unwrap();
+
+    assert_eq!(recorder.texts(), vec!["First line"]);
+}
+
+#[test]
+fn test_zero_reset_disables_joining() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, Duration::ZERO);
+    let start = Instant::now();
+
+    typist.type_text_in("One.", None, start).unwrap();
+    typist.type_text_in("Two.", None, start).unwrap();
+
+    assert_eq!(recorder.texts(), vec!["One.", "Two."]);
+}
+
+#[test]
+fn test_undo_deletes_last_utterance() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, RESET);
+    let start = Instant::now();
+
+    typist.type_text_in("Hello.", app("Notes"), start).unwrap();
+    typist.finish_utterance().unwrap();
+    // Typed in two pieces, as with stable-prefix streaming
+    typist.type_text_in("Ünïcode", app("Notes"), start).unwrap();
+    typist.type_text_in(" text", app("Notes"), start).unwrap();
+    typist.finish_utterance().unwrap();
+
+    assert_eq!(
+        typist.undo_last_in(app("Notes")).unwrap(),
+        " Ünïcode text".chars().count()
+    );
+    assert_eq!(typist.undo_last_in(app("Notes")).unwrap(), 6);
+    assert!(typist.undo_last_in(app("Notes")).is_err());
+    assert_eq!(recorder.deletions(), vec![13, 6]);
+}
+
+#[test]
+fn test_undo_restores_typing_context() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, RESET);
+    let start = Instant::now();
+
+    typist.type_text_in("Hello,", None, start).unwrap();
+    typist.finish_utterance().unwrap();
+    typist.type_text_in("wrong.", None, start).unwrap();
+    typist.finish_utterance().unwrap();
+    typist.undo_last_in(None).unwrap();
+    typist.type_text_in("world", None, start).unwrap();
+
+    assert_eq!(recorder.texts(), vec!["Hello,", " wrong.", " world"]);
+}
+
+#[test]
+fn test_declined_text_not_undone() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, RESET);
+
+    recorder.set_declining(true);
+    typist
+        .type_text_in("Outside the allowlist", app("Chat"), Instant::now())
+        .unwrap();
+    assert!(!typist.finish_utterance().unwrap());
+
+    recorder.set_declining(false);
+    let err = typist.undo_last_in(app("Chat")).unwrap_err();
+    assert!(format!("{err:#}").contains("Nothing to undo"));
+    assert!(recorder.deletions().is_empty());
+}
+
+#[test]
+fn test_undo_refused_after_app_change() {
+    let recorder = RecordingInjector::new();
+    let mut typist = typist(&recorder, RESET);
+
+    typist
+        .type_text_in("Secret", app("Editor"), Instant::now())
+        .unwrap();
+    let err = typist.undo_last_in(app("Chat")).unwrap_err();
+    assert!(format!("{err:#}").contains("Editor"));
+    assert!(recorder.deletions().is_empty());
+
+    // Still there for when the app is back in front
+    assert_eq!(typist.undo_last_in(app("Editor")).unwrap(), 6);
+}
+
+#[test]
+fn test_history_shared_across_typists() {
+    let recorder = RecordingInjector::new();
+    let state = SharedTypingState::default();
+    let mut session = Typist::new(Box::new(recorder.clone()), RESET, Arc::clone(&state));
+    session.type_text("Hello").unwrap();
+    session.finish_utterance().unwrap();
+
+    let mut undo = Typist::new(Box::new(recorder.clone()), RESET, state);
+    assert_eq!(undo.undo_last().unwrap(), 5);
+    assert_eq!(recorder.deletions(), vec![5]);
+}
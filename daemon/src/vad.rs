@@ -24,6 +24,9 @@ pub const VAD_CHUNK_SIZES: [usize; 3] = [512, 1024, 1536];
 /// Default speech probability threshold.
 pub const DEFAULT_THRESHOLD: f32 = 0.5;
 
+/// Default probability below which ongoing speech counts as silence.
+pub const DEFAULT_END_THRESHOLD: f32 = 0.35;
+
 /// VAD event indicating speech state changes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VadEvent {
@@ -38,6 +41,9 @@ pub enum VadEvent {
 pub struct VadConfig {
     /// Probability threshold for considering audio as speech.
     pub threshold: f32,
+    /// Once speaking, probability below which audio counts as silence.
+    /// Lower than `threshold` so speech does not flap on borderline chunks.
+    pub end_threshold: f32,
     /// Minimum consecutive speech chunks before triggering SpeechStart.
     pub min_speech_chunks: usize,
     /// Minimum consecutive silence chunks before triggering SpeechEnd.
@@ -48,6 +54,7 @@ impl Default for VadConfig {
     fn default() -> Self {
         Self {
             threshold: DEFAULT_THRESHOLD,
+            end_threshold: DEFAULT_END_THRESHOLD,
             min_speech_chunks: 2,
             min_silence_chunks: 8,
         }
@@ -76,11 +83,12 @@ impl VadStateMachine {
 
     /// Process a speech probability and return any state change event.
     pub fn process(&mut self, probability: f32) -> Option<VadEvent> {
-        let is_speech = probability >= self.config.threshold;
+        let threshold = self.active_threshold();
+        let is_speech = probability >= threshold;
 
         trace!(
             probability = probability,
-            threshold = self.config.threshold,
+            threshold = threshold,
             is_speech = is_speech,
             speaking = self.is_speaking,
             speech_chunks = self.speech_chunk_count,
@@ -116,6 +124,15 @@ impl VadStateMachine {
         self.is_speaking
     }
 
+    /// Threshold a probability is compared against in the current state.
+    pub fn active_threshold(&self) -> f32 {
+        if self.is_speaking {
+            self.config.end_threshold
+        } else {
+            self.config.threshold
+        }
+    }
+
     /// Reset the state machine.
     pub fn reset(&mut self) {
         self.is_speaking = false;
@@ -124,6 +141,18 @@ impl VadStateMachine {
     }
 }
 
+/// Estimates how likely chunks of 16kHz mono audio contain speech.
+pub trait SpeechDetector: Send {
+    /// Speech probability of a chunk of exactly `chunk_size()` samples.
+    fn process_chunk(&mut self, audio: &[f32]) -> Result<f32>;
+
+    /// Samples per chunk.
+    fn chunk_size(&self) -> usize;
+
+    /// Forget state carried over from earlier chunks.
+    fn reset(&mut self);
+}
+
 /// Voice Activity Detector using Silero VAD ONNX model.
 pub struct VoiceActivityDetector {
     session: Session,
@@ -278,6 +307,61 @@ impl VoiceActivityDetector {
     }
 }
 
+impl SpeechDetector for VoiceActivityDetector {
+    fn process_chunk(&mut self, audio: &[f32]) -> Result<f32> {
+        VoiceActivityDetector::process_chunk(self, audio)
+    }
+
+    fn chunk_size(&self) -> usize {
+        VoiceActivityDetector::chunk_size(self)
+    }
+
+    fn reset(&mut self) {
+        VoiceActivityDetector::reset(self);
+    }
+}
+
+/// Detector that treats every chunk louder than a threshold as speech.
+///
+/// Needs no model and is deterministic, so tests can drive the pipeline
+/// with synthetic audio.
+#[derive(Debug, Clone)]
+pub struct EnergyDetector {
+    /// RMS level from which a chunk counts as speech.
+    rms_threshold: f32,
+    chunk_size: usize,
+}
+
+impl EnergyDetector {
+    /// Create a detector for chunks of `chunk_size` samples.
+    pub fn new(rms_threshold: f32, chunk_size: usize) -> Self {
+        Self {
+            rms_threshold,
+            chunk_size,
+        }
+    }
+}
+
+impl SpeechDetector for EnergyDetector {
+    fn process_chunk(&mut self, audio: &[f32]) -> Result<f32> {
+        if audio.len() != self.chunk_size {
+            anyhow::bail!(
+                "Audio chunk size {} doesn't match expected {}",
+                audio.len(),
+                self.chunk_size
+            );
+        }
+        let rms = (audio.iter().map(|x| x * x).sum::<f32>() / audio.len() as f32).sqrt();
+        Ok(if rms >= self.rms_threshold { 1.0 } else { 0.0 })
+    }
+
+    fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    fn reset(&mut self) {}
+}
+
 #[cfg(test)]
 #[path = "vad_test.rs"]
 mod tests;
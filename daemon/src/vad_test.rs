@@ -10,6 +10,7 @@ fn test_state_machine_initial_state() {
 fn test_state_machine_speech_start() {
     let config = VadConfig {
         threshold: 0.5,
+        end_threshold: 0.5,
         min_speech_chunks: 2,
         min_silence_chunks: 3,
     };
@@ -32,6 +33,7 @@ fn test_state_machine_speech_start() {
 fn test_state_machine_speech_end() {
     let config = VadConfig {
         threshold: 0.5,
+        end_threshold: 0.5,
         min_speech_chunks: 1,
         min_silence_chunks: 2,
     };
@@ -53,6 +55,7 @@ fn test_state_machine_speech_end() {
 fn test_state_machine_threshold() {
     let config = VadConfig {
         threshold: 0.7,
+        end_threshold: 0.7,
         min_speech_chunks: 1,
         min_silence_chunks: 1,
     };
@@ -75,6 +78,7 @@ fn test_state_machine_threshold() {
 fn test_state_machine_interrupted_speech() {
     let config = VadConfig {
         threshold: 0.5,
+        end_threshold: 0.5,
         min_speech_chunks: 3,
         min_silence_chunks: 3,
     };
@@ -101,6 +105,7 @@ fn test_state_machine_interrupted_speech() {
 fn test_state_machine_reset() {
     let config = VadConfig {
         threshold: 0.5,
+        end_threshold: 0.5,
         min_speech_chunks: 1,
         min_silence_chunks: 1,
     };
@@ -119,6 +124,42 @@ fn test_state_machine_reset() {
 fn test_default_config() {
     let config = VadConfig::default();
     assert!((config.threshold - 0.5).abs() < f32::EPSILON);
+    assert!((config.end_threshold - 0.35).abs() < f32::EPSILON);
     assert_eq!(config.min_speech_chunks, 2);
     assert_eq!(config.min_silence_chunks, 8);
 }
+
+#[test]
+fn test_state_machine_end_threshold_hysteresis() {
+    let config = VadConfig {
+        threshold: 0.5,
+        end_threshold: 0.3,
+        min_speech_chunks: 1,
+        min_silence_chunks: 2,
+    };
+    let mut sm = VadStateMachine::new(config);
+
+    // Below the start threshold: not speech yet
+    assert_eq!(sm.process(0.4), None);
+    assert!(!sm.is_speaking());
+
+    assert_eq!(sm.process(0.6), Some(VadEvent::SpeechStart));
+
+    // Borderline chunks above end_threshold keep speech going
+    assert_eq!(sm.process(0.4), None);
+    assert_eq!(sm.process(0.4), None);
+    assert!(sm.is_speaking());
+
+    // Below end_threshold counts as silence
+    assert_eq!(sm.process(0.2), None);
+    assert_eq!(sm.process(0.2), Some(VadEvent::SpeechEnd));
+}
+
+#[test]
+fn test_energy_detector_thresholds_rms() {
+    let mut detector = EnergyDetector::new(0.1, 512);
+    assert_eq!(detector.process_chunk(&[0.0; 512]).unwrap(), 0.0);
+    assert_eq!(detector.process_chunk(&[0.05; 512]).unwrap(), 0.0);
+    assert_eq!(detector.process_chunk(&[-0.2; 512]).unwrap(), 1.0);
+    assert!(detector.process_chunk(&[0.2; 256]).is_err());
+}
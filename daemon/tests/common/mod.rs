@@ -0,0 +1,194 @@
+//! Helpers shared by the end-to-end tests.
+//!
+//! Each test binary uses only some of them.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tonic::Streaming;
+use tonic::transport::Channel;
+use vcm_daemon::config::{AudioSourceKind, Config, InitialState};
+use vcm_daemon::daemon::{DaemonPaths, run_with_engine};
+use vcm_daemon::engine::{Engine, ModelOverride};
+use vcm_daemon::inject::{RecordingInjector, recording_injector_factory};
+use vcm_daemon::transcribe::ScriptedTranscriber;
+use vcm_proto::vcm_client::VcmClient;
+use vcm_proto::{Empty, Event, State, status::Status as StatusVariant};
+
+/// Connect to the daemon, retrying until the socket is ready.
+pub async fn connect_with_retry(socket_path: &Path, timeout: Duration) -> VcmClient<Channel> {
+    let start = std::time::Instant::now();
+    loop {
+        if start.elapsed() > timeout {
+            panic!("Timed out waiting for daemon at {}", socket_path.display());
+        }
+        let path = socket_path.to_path_buf();
+        let result = tonic::transport::Endpoint::try_from("http://[::]:50051")
+            .unwrap()
+            .connect_with_connector(tower::service_fn(move |_: tonic::transport::Uri| {
+                let p = path.clone();
+                async move {
+                    let stream = tokio::net::UnixStream::connect(p).await?;
+                    Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+                }
+            }))
+            .await;
+        match result {
+            Ok(channel) => return VcmClient::new(channel),
+            Err(_) => tokio::time::sleep(Duration::from_millis(50)).await,
+        }
+    }
+}
+
+pub fn extract_state(status: vcm_proto::Status) -> State {
+    match status.status {
+        Some(StatusVariant::Healthy(h)) => State::try_from(h.state).unwrap(),
+        other => panic!("Expected Healthy status, got: {:?}", other),
+    }
+}
+
+/// Half a second of silence, then `count` one-second tones each followed by
+/// 1.5 seconds of silence.
+pub fn tone_bursts(sample_rate: u32, count: usize) -> Vec<f32> {
+    let secs = |s: f32| (s * sample_rate as f32) as usize;
+    let mut samples = vec![0.0; secs(0.5)];
+    for _ in 0..count {
+        samples.extend((0..secs(1.0)).map(|i| {
+            0.3 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin()
+        }));
+        samples.extend(vec![0.0; secs(1.5)]);
+    }
+    samples
+}
+
+/// Write `samples` as a 16-bit mono WAV file.
+pub fn write_wav(path: &Path, samples: &[f32], sample_rate: u32) {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+    for &sample in samples {
+        writer
+            .write_sample((sample * f32::from(i16::MAX)) as i16)
+            .unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+/// An in-process daemon replaying an audio file through the full pipeline:
+/// file source, energy-based VAD, scripted transcriber and a recording
+/// injector, controlled over gRPC.
+pub struct Harness {
+    pub client: VcmClient<Channel>,
+    /// Everything the daemon typed.
+    pub injected: RecordingInjector,
+    /// The transcriber's script and the requests it received.
+    pub transcriber: ScriptedTranscriber,
+    socket: PathBuf,
+    pid: PathBuf,
+    daemon: JoinHandle<anyhow::Result<()>>,
+    _dir: tempfile::TempDir,
+}
+
+impl Harness {
+    /// Start a paused daemon that replays `audio` when listening starts and
+    /// answers utterances with `script`, in order.
+    pub async fn start(audio: &[f32], sample_rate: u32, script: &[&str]) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let audio_path = dir.path().join("fixture.wav");
+        write_wav(&audio_path, audio, sample_rate);
+
+        let mut config = Config::default();
+        config.audio.source = AudioSourceKind::File;
+        config.audio.path = Some(audio_path);
+        config.daemon.initial_state = InitialState::Paused;
+
+        let transcriber = ScriptedTranscriber::new(script.iter().copied());
+        let injected = RecordingInjector::new();
+        let mut engine = Engine::new(config.clone()).unwrap();
+        engine.set_model_override(ModelOverride::scripted(transcriber.clone()));
+
+        let paths = DaemonPaths {
+            socket: dir.path().join("daemon.sock"),
+            pid: dir.path().join("daemon.pid"),
+        };
+        let socket = paths.socket.clone();
+        let pid = paths.pid.clone();
+        let factory = recording_injector_factory(&injected);
+        let daemon =
+            tokio::spawn(async move { run_with_engine(paths, config, engine, factory).await });
+
+        let mut client = connect_with_retry(&socket, Duration::from_secs(5)).await;
+        wait_for_state(&mut client, State::Paused, Duration::from_secs(5)).await;
+
+        Self {
+            client,
+            injected,
+            transcriber,
+            socket,
+            pid,
+            daemon,
+            _dir: dir,
+        }
+    }
+
+    /// Subscribe to daemon events.
+    pub async fn subscribe(&mut self) -> Streaming<Event> {
+        self.client.subscribe(Empty {}).await.unwrap().into_inner()
+    }
+
+    /// Shut the daemon down and check that it cleaned up.
+    pub async fn shutdown(mut self) {
+        self.client.shutdown(Empty {}).await.unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(5), self.daemon)
+            .await
+            .expect("Daemon did not shut down in time")
+            .expect("Daemon task panicked");
+        result.expect("Daemon returned error");
+        assert!(!self.socket.exists(), "Socket should be cleaned up");
+        assert!(!self.pid.exists(), "PID file should be cleaned up");
+    }
+}
+
+/// Poll the daemon status until it reports `expected`.
+pub async fn wait_for_state(client: &mut VcmClient<Channel>, expected: State, timeout: Duration) {
+    let start = std::time::Instant::now();
+    loop {
+        let status = client.get_status(Empty {}).await.unwrap().into_inner();
+        let state = extract_state(status);
+        if state == expected {
+            return;
+        }
+        if start.elapsed() > timeout {
+            panic!("Timed out waiting for {expected:?}, daemon is {state:?}");
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+/// Collect final transcription texts from `events` until there are `count`.
+pub async fn final_transcriptions(
+    events: &mut Streaming<Event>,
+    count: usize,
+    timeout: Duration,
+) -> Vec<String> {
+    let mut texts = Vec::new();
+    tokio::time::timeout(timeout, async {
+        while texts.len() < count {
+            let event = events.message().await.unwrap().expect("Event stream ended");
+            if let Some(vcm_proto::event::Event::Transcription(transcription)) = event.event
+                && !transcription.is_partial
+            {
+                texts.push(transcription.text);
+            }
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("Timed out waiting for transcriptions, got {texts:?}"));
+    texts
+}
@@ -3,50 +3,17 @@
 //! Starts the daemon in-process with a temporary socket/PID directory,
 //! then exercises the full control flow through the gRPC client.
 //!
-//! Note: start_listening/stop_listening require initialized engine (real models),
-//! so we test the control plane without audio here.
+//! Note: this uses the real models, which are not downloaded here, so only the
+//! control plane is tested. `e2e_pipeline.rs` runs audio with stand-in models.
+
+mod common;
 
 use std::time::Duration;
 
+use common::{connect_with_retry, extract_state};
 use vcm_daemon::config::Config;
 use vcm_daemon::daemon::{DaemonPaths, run_with_paths_and_config};
-use vcm_proto::vcm_client::VcmClient;
-use vcm_proto::{Empty, State, status::Status as StatusVariant};
-
-/// Connect to the daemon, retrying until the socket is ready.
-async fn connect_with_retry(
-    socket_path: &std::path::Path,
-    timeout: Duration,
-) -> VcmClient<tonic::transport::Channel> {
-    let start = std::time::Instant::now();
-    loop {
-        if start.elapsed() > timeout {
-            panic!("Timed out waiting for daemon at {}", socket_path.display());
-        }
-        let path = socket_path.to_path_buf();
-        let result = tonic::transport::Endpoint::try_from("http://[::]:50051")
-            .unwrap()
-            .connect_with_connector(tower::service_fn(move |_: tonic::transport::Uri| {
-                let p = path.clone();
-                async move {
-                    let stream = tokio::net::UnixStream::connect(p).await?;
-                    Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
-                }
-            }))
-            .await;
-        match result {
-            Ok(channel) => return VcmClient::new(channel),
-            Err(_) => tokio::time::sleep(Duration::from_millis(50)).await,
-        }
-    }
-}
-
-fn extract_state(status: vcm_proto::Status) -> State {
-    match status.status {
-        Some(StatusVariant::Healthy(h)) => State::try_from(h.state).unwrap(),
-        other => panic!("Expected Healthy status, got: {:?}", other),
-    }
-}
+use vcm_proto::{Empty, State};
 
 #[tokio::test]
 async fn test_daemon_grpc_lifecycle() {
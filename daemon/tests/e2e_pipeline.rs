@@ -0,0 +1,86 @@
+//! End-to-end test of the audio pipeline through the gRPC server.
+//!
+//! Replays synthetic audio through VAD, transcription and injection with
+//! stand-in models, so it needs no model downloads, microphone or keyboard.
+
+mod common;
+
+use std::time::Duration;
+
+use common::{Harness, final_transcriptions, tone_bursts, wait_for_state};
+use vcm_proto::{Empty, State};
+
+#[tokio::test]
+async fn test_utterances_transcribed_and_injected() {
+    let audio = tone_bursts(48000, 2);
+    let mut harness = Harness::start(&audio, 48000, &["Hello there.", "General Kenobi."]).await;
+    let mut events = harness.subscribe().await;
+
+    harness.client.start_listening(Empty {}).await.unwrap();
+    wait_for_state(
+        &mut harness.client,
+        State::Listening,
+        Duration::from_secs(5),
+    )
+    .await;
+
+    let texts = final_transcriptions(&mut events, 2, Duration::from_secs(10)).await;
+    assert_eq!(texts, vec!["Hello there.", "General Kenobi."]);
+    // The second utterance continues the text typed before it
+    assert_eq!(
+        harness.injected.texts(),
+        vec!["Hello there.", " General Kenobi."]
+    );
+
+    let requests = harness.transcriber.requests();
+    assert_eq!(requests.len(), 2);
+    // Each utterance holds about a second of tone, resampled to 16kHz
+    assert!(requests.iter().all(|request| request.samples >= 16000));
+
+    // An open subscription holds up graceful shutdown
+    drop(events);
+    harness.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_silence_injects_nothing() {
+    let silence = vec![0.0; 16000 * 3];
+    let mut harness = Harness::start(&silence, 16000, &["Never said."]).await;
+
+    harness.client.start_listening(Empty {}).await.unwrap();
+    // The file source finishes quickly; give the engine time to drain it
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    assert!(harness.injected.texts().is_empty());
+    assert!(harness.transcriber.requests().is_empty());
+
+    harness.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_undo_deletes_last_utterance() {
+    let audio = tone_bursts(48000, 3);
+    let mut harness = Harness::start(
+        &audio,
+        48000,
+        &["Hello there.", "Wrong words.", "Scratch that."],
+    )
+    .await;
+    let mut events = harness.subscribe().await;
+
+    harness.client.start_listening(Empty {}).await.unwrap();
+    final_transcriptions(&mut events, 3, Duration::from_secs(10)).await;
+    // The spoken command is not typed and deletes the utterance before it
+    assert_eq!(
+        harness.injected.texts(),
+        vec!["Hello there.", " Wrong words."]
+    );
+    assert_eq!(harness.injected.deletions(), vec![13]);
+
+    let response = harness.client.undo_last(Empty {}).await.unwrap();
+    assert_eq!(response.into_inner().deleted_chars, 12);
+    assert!(harness.client.undo_last(Empty {}).await.is_err());
+
+    drop(events);
+    harness.shutdown().await;
+}
@@ -6,7 +6,10 @@ use anyhow::Context as _;
 use tao::event_loop::EventLoopProxy;
 use vcm_proto::event::Event as EventType;
 use vcm_proto::init_progress::Progress;
-use vcm_proto::{Empty, SetLanguageRequest, State as ProtoState, status::Status as StatusVariant};
+use vcm_proto::{
+    Empty, SetLanguageRequest, SetTaskRequest, State as ProtoState, Task,
+    status::Status as StatusVariant,
+};
 
 use vcm_common::client;
 use vcm_common::dirs;
@@ -27,6 +30,8 @@ pub enum Command {
     StartListening,
     StopListening,
     SetLanguage(String),
+    /// Translate speech to English (`true`) or transcribe it as spoken.
+    SetTranslate(bool),
     Shutdown,
     InstallCli,
 }
@@ -178,6 +183,20 @@ async fn run_connected(
                     send_language(event_proxy, resp.into_inner());
                 }
             }
+            Ok(Command::SetTranslate(translate)) => {
+                let task = if translate {
+                    Task::Translate
+                } else {
+                    Task::Transcribe
+                };
+                // Re-read on failure too, so the menu reverts if the daemon refused
+                let _ = grpc_client
+                    .set_task(SetTaskRequest { task: task.into() })
+                    .await;
+                if let Ok(resp) = grpc_client.get_language(Empty {}).await {
+                    send_language(event_proxy, resp.into_inner());
+                }
+            }
             Ok(Command::InstallCli) => {
                 let current_exe = match std::env::current_exe() {
                     Ok(p) => p,
@@ -240,7 +259,8 @@ fn process_event(event: vcm_proto::Event) -> Option<AppState> {
             Progress::Ready(_) => Some(AppState::Paused),
         },
         EventType::DaemonError(err) => Some(AppState::Error(err.message)),
-        EventType::Transcription(_) => None,
+        // The old model keeps serving while a new one loads
+        EventType::Transcription(_) | EventType::ModelSwitch(_) => None,
     }
 }
 
@@ -262,6 +282,7 @@ fn send_state(proxy: &EventLoopProxy<UserEvent>, state: AppState) {
 fn send_language(proxy: &EventLoopProxy<UserEvent>, resp: vcm_proto::GetLanguageResponse) {
     use crate::state::LanguageSelection;
 
+    let translate = resp.task() == Task::Translate;
     let active = if resp.language.is_empty() || resp.language.eq_ignore_ascii_case("auto") {
         LanguageSelection::Auto
     } else {
@@ -270,6 +291,7 @@ fn send_language(proxy: &EventLoopProxy<UserEvent>, resp: vcm_proto::GetLanguage
     let info = LanguageInfo {
         active,
         available: resp.available_languages,
+        translate,
     };
     let _ = proxy.send_event(UserEvent::App(AppEvent::LanguageChanged(info)));
 }
@@ -76,6 +76,14 @@ impl App {
                 }
                 _ => {}
             }
+        } else if event.id == self.menu_items.translate.id() {
+            self.language.translate = !self.language.translate;
+            let _ = self
+                .cmd_tx
+                .send(Command::SetTranslate(self.language.translate));
+            self.menu_items
+                .translate
+                .set_checked(self.language.translate);
         } else if self
             .menu_items
             .install_cli
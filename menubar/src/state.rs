@@ -26,11 +26,13 @@ impl LanguageSelection {
     }
 }
 
-/// Language configuration: the active language and the list of available languages.
+/// Language configuration: the active language, the list of available languages
+/// and whether speech is translated to English.
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct LanguageInfo {
     pub active: LanguageSelection,
     pub available: Vec<String>,
+    pub translate: bool,
 }
 
 /// Application state derived from daemon status.
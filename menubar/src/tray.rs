@@ -9,6 +9,8 @@ pub struct MenuItems {
     pub toggle: MenuItem,
     /// Language check menu items: each entry is (CheckMenuItem, language code).
     pub language_items: Vec<(CheckMenuItem, String)>,
+    /// Switches between transcribing and translating to English.
+    pub translate: CheckMenuItem,
     /// `None` when vcmctl is already installed (item is omitted from the menu).
     pub install_cli: Option<MenuItem>,
     pub quit: MenuItem,
@@ -36,6 +38,7 @@ pub fn build_menu(state: &AppState, language: &LanguageInfo) -> (Menu, MenuItems
 
     // Build language items if there are available languages
     let language_items = build_language_items(language);
+    let translate = CheckMenuItem::new("Translate to English", true, language.translate, None);
 
     // Assemble the menu
     menu.append_items(&[&status, &PredefinedMenuItem::separator()])
@@ -56,6 +59,11 @@ pub fn build_menu(state: &AppState, language: &LanguageInfo) -> (Menu, MenuItems
             .expect("failed to build menu");
     }
 
+    if state.has_toggle() {
+        menu.append_items(&[&translate, &PredefinedMenuItem::separator()])
+            .expect("failed to build menu");
+    }
+
     if let Some(install_cli) = &install_cli {
         menu.append_items(&[install_cli, &PredefinedMenuItem::separator()])
             .expect("failed to build menu");
@@ -67,6 +75,7 @@ pub fn build_menu(state: &AppState, language: &LanguageInfo) -> (Menu, MenuItems
         MenuItems {
             toggle,
             language_items,
+            translate,
             install_cli,
             quit,
         },